@@ -0,0 +1,57 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    SamplingMode,
+};
+use datadog_grok::{
+    parse_grok::{parse_grok, GrokRuleSet},
+    parse_grok_rules::parse_grok_rules,
+};
+
+fn parse_grok_bench(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> =
+        c.benchmark_group("datadog_grok::parse_grok::parse_grok");
+    group.sampling_mode(SamplingMode::Auto);
+
+    // dozens of rules, only the last one matches
+    let mut patterns = (0..50)
+        .map(|i| {
+            format!(
+                r#"rule{} %{{word:level}} %{{integer:status}} %{{data:message}}"#,
+                i
+            )
+        })
+        .collect::<Vec<String>>();
+    patterns.push("%{word:level} %{integer:status} %{data:message}".to_string());
+    let rules = parse_grok_rules(&patterns, BTreeMap::new()).expect("couldn't parse rules");
+    let rule_set = GrokRuleSet::new(rules.clone());
+    let message = "info 200 a fairly long message, which does not match the first rules";
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| black_box(parse_grok(message, &rules, false)))
+    });
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| black_box(rule_set.parse(message, false)))
+    });
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default()
+        .warm_up_time(Duration::from_secs(5))
+        .measurement_time(Duration::from_secs(60))
+        // degree of noise to ignore in measurements, here 1%
+        .noise_threshold(0.01)
+        // likelihood of noise registering as difference, here 5%
+        .significance_level(0.05)
+        // likelihood of capturing the true runtime, here 95%
+        .confidence_level(0.95)
+        // total number of bootstrap resamples, higher is less noisy but slower
+        .nresamples(100_000)
+        // total samples to collect within the set measurement time
+        .sample_size(150);
+    targets = parse_grok_bench
+);
+criterion_main!(benches);
@@ -0,0 +1,59 @@
+//! Checksum helpers shared by grok filters and other consumers that need to
+//! validate matched identifiers, e.g. credit card numbers.
+
+/// Validates a number with the [Luhn algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm).
+///
+/// Spaces and dashes are stripped before validation, so `4111 1111 1111 1111` and
+/// `4111-1111-1111-1111` are treated the same as `4111111111111111`.
+/// Any other non-digit character, or an input with fewer than two digits, is invalid.
+pub fn luhn_valid(digits: &str) -> bool {
+    let mut sum = 0;
+    let mut count = 0;
+
+    for c in digits.chars().rev().filter(|c| *c != ' ' && *c != '-') {
+        let digit = match c.to_digit(10) {
+            Some(digit) => digit,
+            None => return false,
+        };
+        sum += if count % 2 == 1 {
+            let doubled = digit * 2;
+            if doubled > 9 {
+                doubled - 9
+            } else {
+                doubled
+            }
+        } else {
+            digit
+        };
+        count += 1;
+    }
+
+    count > 1 && sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_luhn_checksums() {
+        let tests = vec![
+            ("4111111111111111", true),
+            ("4111 1111 1111 1111", true),
+            ("4111-1111-1111-1111", true),
+            ("378282246310005", true),
+            ("6011111111111117", true),
+            ("79927398713", true),
+            ("4111111111111112", false),
+            ("79927398710", false),
+            ("4111x1111", false),
+            ("0", false),
+            ("", false),
+            ("- -", false),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(luhn_valid(input), expected, "input: {:?}", input);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use value::Value;
+
+use crate::{grok_filter::GrokFilter, parse_grok::Error as GrokRuntimeError};
+
+/// Returns the number of bytes in a given unit, SI units(KB, MB, ...) are powers of 1000, IEC units(KiB, MiB, ...) are powers of 1024.
+fn unit_bytes(unit: &str) -> Option<f64> {
+    let unit = unit.to_ascii_lowercase();
+    let (prefix, base) = match unit.strip_suffix("ib") {
+        Some(prefix) => (prefix, 1024_f64),
+        None => (unit.strip_suffix('b').unwrap_or(&unit), 1000_f64),
+    };
+    let exponent = match prefix {
+        "" => 0,
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        "p" => 5,
+        _ => return None,
+    };
+    Some(base.powi(exponent))
+}
+
+/// Parses a human-readable size, e.g. `10KB`, `1.5 MiB` or `2G`, into a number of bytes.
+/// A number without a unit is considered to be a number of bytes.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match value {
+        Value::Bytes(bytes) => {
+            let input = String::from_utf8_lossy(bytes);
+            let input = input.trim();
+            let unit_start = input
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or_else(|| input.len());
+            let (number, unit) = input.split_at(unit_start);
+
+            match (number.parse::<f64>(), unit_bytes(unit.trim_start())) {
+                (Ok(number), Some(unit_bytes)) => Ok(Value::Integer((number * unit_bytes) as i64)),
+                _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                    filter.to_string(),
+                    value.to_string(),
+                )),
+            }
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn byte_size(input: &str) -> Result<Value, GrokRuntimeError> {
+        apply_filter(&Value::from(input), &GrokFilter::ByteSize)
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(byte_size("10KB"), Ok(Value::Integer(10_000)));
+        assert_eq!(byte_size("1.5MiB"), Ok(Value::Integer(1_572_864)));
+        assert_eq!(byte_size("1.5 MiB"), Ok(Value::Integer(1_572_864)));
+        assert_eq!(byte_size("2G"), Ok(Value::Integer(2_000_000_000)));
+        assert_eq!(byte_size("4kib"), Ok(Value::Integer(4096)));
+        assert_eq!(byte_size("512"), Ok(Value::Integer(512)));
+        assert_eq!(byte_size("512B"), Ok(Value::Integer(512)));
+    }
+
+    #[test]
+    fn fails_on_invalid_sizes() {
+        assert!(byte_size("10XB").is_err());
+        assert!(byte_size("KB").is_err());
+    }
+}
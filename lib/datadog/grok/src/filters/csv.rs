@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use csv::ReaderBuilder;
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// Creates a `csv` filter from the function arguments: `csv([delimiter[, headers]])`.
+/// The delimiter must be a single character(`,` by default), headers are
+/// separated with the same delimiter, e.g. `csv(";", "first;second")`.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    let args = f.args.as_deref().unwrap_or_default();
+    if args.len() > 2 {
+        return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone()));
+    }
+
+    let delimiter = match args.get(0) {
+        Some(FunctionArgument::Arg(Value::Bytes(bytes))) if bytes.len() == 1 => bytes[0],
+        None => b',',
+        _ => return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    };
+
+    let headers = match args.get(1) {
+        Some(FunctionArgument::Arg(Value::Bytes(bytes))) => Some(
+            parse_record(bytes, delimiter)
+                .ok()
+                .flatten()
+                .map(|headers| {
+                    headers
+                        .iter()
+                        .map(|header| String::from_utf8_lossy(header).to_string())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|headers| !headers.is_empty())
+                .ok_or_else(|| GrokStaticError::InvalidFunctionArguments(f.name.clone()))?,
+        ),
+        None => None,
+        _ => return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    };
+
+    Ok(GrokFilter::Csv { delimiter, headers })
+}
+
+/// Parses a single CSV row into an array of values or, if headers are given, into an object.
+/// If the row has fewer columns than headers, the missing columns are set to null,
+/// extra columns without a header are ignored.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match (value, filter) {
+        (Value::Bytes(bytes), GrokFilter::Csv { delimiter, headers }) => {
+            let record = parse_record(bytes, *delimiter)
+                .ok()
+                .flatten()
+                .ok_or_else(|| {
+                    GrokRuntimeError::FailedToApplyFilter(filter.to_string(), value.to_string())
+                })?;
+            let mut values = record
+                .iter()
+                .map(|v| Value::Bytes(Bytes::copy_from_slice(v)));
+
+            match headers {
+                Some(headers) => Ok(headers
+                    .iter()
+                    .map(|header| (header.to_owned(), values.next().unwrap_or(Value::Null)))
+                    .collect::<BTreeMap<String, Value>>()
+                    .into()),
+                None => Ok(values.collect::<Vec<Value>>().into()),
+            }
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+fn parse_record(input: &[u8], delimiter: u8) -> Result<Option<csv::ByteRecord>, csv::Error> {
+    ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(delimiter)
+        .from_reader(input)
+        .into_byte_records()
+        .next()
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn csv_filter(delimiter: u8, headers: Option<Vec<&str>>) -> GrokFilter {
+        GrokFilter::Csv {
+            delimiter,
+            headers: headers.map(|h| h.into_iter().map(String::from).collect()),
+        }
+    }
+
+    #[test]
+    fn parses_quoted_fields() {
+        let result = apply_filter(
+            &Value::from(r#"a,"b,c","d ""e""""#),
+            &csv_filter(b',', None),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec!["a".into(), "b,c".into(), r#"d "e""#.into()])
+        );
+    }
+
+    #[test]
+    fn maps_headers() {
+        let result = apply_filter(
+            &Value::from("1;bob;admin"),
+            &csv_filter(b';', Some(vec!["id", "name", "role"])),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::from(btreemap! {
+                "id" => "1",
+                "name" => "bob",
+                "role" => "admin",
+            })
+        );
+    }
+
+    #[test]
+    fn handles_mismatched_column_counts() {
+        let filter = csv_filter(b',', Some(vec!["a", "b"]));
+
+        assert_eq!(
+            apply_filter(&Value::from("1"), &filter).unwrap(),
+            Value::from(btreemap! {
+                "a" => "1",
+                "b" => Value::Null,
+            })
+        );
+        assert_eq!(
+            apply_filter(&Value::from("1,2,3"), &filter).unwrap(),
+            Value::from(btreemap! {
+                "a" => "1",
+                "b" => "2",
+            })
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// A unit of time a duration can be expressed in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DurationUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+}
+
+impl DurationUnit {
+    fn nanoseconds(&self) -> f64 {
+        match self {
+            DurationUnit::Nanoseconds => 1.0,
+            DurationUnit::Microseconds => 1e3,
+            DurationUnit::Milliseconds => 1e6,
+            DurationUnit::Seconds => 1e9,
+            DurationUnit::Minutes => 60.0 * 1e9,
+            DurationUnit::Hours => 3600.0 * 1e9,
+        }
+    }
+}
+
+impl FromStr for DurationUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ns" => Ok(DurationUnit::Nanoseconds),
+            "us" | "µs" => Ok(DurationUnit::Microseconds),
+            "ms" => Ok(DurationUnit::Milliseconds),
+            "s" => Ok(DurationUnit::Seconds),
+            "m" => Ok(DurationUnit::Minutes),
+            "h" => Ok(DurationUnit::Hours),
+            _ => Err(format!("unknown time unit '{}'", s)),
+        }
+    }
+}
+
+/// Creates a `duration([unit])` filter, the output unit defaults to nanoseconds.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    match f.args.as_deref() {
+        None | Some([]) => Ok(GrokFilter::Duration {
+            output_unit: DurationUnit::Nanoseconds,
+        }),
+        Some([FunctionArgument::Arg(Value::Bytes(unit))]) => String::from_utf8_lossy(unit)
+            .parse::<DurationUnit>()
+            .map(|output_unit| GrokFilter::Duration { output_unit })
+            .map_err(|_| GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    }
+}
+
+/// Parses a duration with an optional unit suffix, e.g. `1.5s` or `200ms`, and converts it to
+/// an integer in the output unit of the filter, truncating any fraction, e.g. `1500us` is `1ms`.
+/// A number without a suffix is considered to be in the output unit already.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match (value, filter) {
+        (Value::Bytes(bytes), GrokFilter::Duration { output_unit }) => {
+            let input = String::from_utf8_lossy(bytes);
+            let input = input.trim();
+            let suffix_start = input
+                .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+                .unwrap_or_else(|| input.len());
+            let (number, suffix) = input.split_at(suffix_start);
+
+            let number = number.parse::<f64>().ok();
+            let input_unit = match suffix.trim() {
+                "" => Some(*output_unit),
+                suffix => suffix.parse::<DurationUnit>().ok(),
+            };
+            match (number, input_unit) {
+                (Some(number), Some(input_unit)) => Ok(Value::Integer(
+                    (number * input_unit.nanoseconds() / output_unit.nanoseconds()) as i64,
+                )),
+                _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                    filter.to_string(),
+                    value.to_string(),
+                )),
+            }
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duration(input: &str, output_unit: DurationUnit) -> Result<Value, GrokRuntimeError> {
+        apply_filter(&Value::from(input), &GrokFilter::Duration { output_unit })
+    }
+
+    #[test]
+    fn converts_to_unit() {
+        assert_eq!(
+            duration("200ms", DurationUnit::Nanoseconds),
+            Ok(Value::Integer(200_000_000))
+        );
+        assert_eq!(
+            duration("1.5s", DurationUnit::Milliseconds),
+            Ok(Value::Integer(1500))
+        );
+        assert_eq!(
+            duration("3m", DurationUnit::Seconds),
+            Ok(Value::Integer(180))
+        );
+    }
+
+    #[test]
+    fn truncates_fractions_of_output_unit() {
+        assert_eq!(
+            duration("1500us", DurationUnit::Milliseconds),
+            Ok(Value::Integer(1))
+        );
+        assert_eq!(
+            duration("1500ms", DurationUnit::Minutes),
+            Ok(Value::Integer(0))
+        );
+        assert_eq!(
+            duration("-1.5s", DurationUnit::Seconds),
+            Ok(Value::Integer(-1))
+        );
+    }
+
+    #[test]
+    fn supports_all_suffixes() {
+        for (input, nanoseconds) in [
+            ("42ns", 42),
+            ("200us", 200_000),
+            ("200µs", 200_000),
+            ("1.5ms", 1_500_000),
+            ("3s", 3_000_000_000),
+            ("2m", 120_000_000_000),
+            ("1h", 3_600_000_000_000),
+        ] {
+            assert_eq!(
+                duration(input, DurationUnit::Nanoseconds),
+                Ok(Value::Integer(nanoseconds)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn treats_bare_numbers_as_output_unit() {
+        assert_eq!(
+            duration("42", DurationUnit::Nanoseconds),
+            Ok(Value::Integer(42))
+        );
+        assert_eq!(
+            duration("42", DurationUnit::Milliseconds),
+            Ok(Value::Integer(42))
+        );
+    }
+
+    #[test]
+    fn fails_on_unknown_suffix() {
+        assert_eq!(
+            duration("3 fortnights", DurationUnit::Seconds),
+            Err(GrokRuntimeError::FailedToApplyFilter(
+                "Duration".to_owned(),
+                r#""3 fortnights""#.to_owned()
+            ))
+        );
+        assert_eq!(
+            duration("3d", DurationUnit::Seconds),
+            Err(GrokRuntimeError::FailedToApplyFilter(
+                "Duration".to_owned(),
+                r#""3d""#.to_owned()
+            ))
+        );
+        assert!(duration("ms", DurationUnit::Seconds).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use std::{collections::BTreeMap, fmt, net::IpAddr, str::FromStr, sync::Arc};
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use tracing::error;
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// A field of a City database record the filter can produce.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoIpField {
+    City,
+    Continent,
+    Country,
+    CountryCode,
+    Latitude,
+    Longitude,
+    Timezone,
+}
+
+const ALL_FIELDS: [GeoIpField; 7] = [
+    GeoIpField::City,
+    GeoIpField::Continent,
+    GeoIpField::Country,
+    GeoIpField::CountryCode,
+    GeoIpField::Latitude,
+    GeoIpField::Longitude,
+    GeoIpField::Timezone,
+];
+
+impl GeoIpField {
+    fn name(&self) -> &'static str {
+        match self {
+            GeoIpField::City => "city",
+            GeoIpField::Continent => "continent",
+            GeoIpField::Country => "country",
+            GeoIpField::CountryCode => "country_code",
+            GeoIpField::Latitude => "latitude",
+            GeoIpField::Longitude => "longitude",
+            GeoIpField::Timezone => "timezone",
+        }
+    }
+}
+
+impl FromStr for GeoIpField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_FIELDS
+            .iter()
+            .find(|field| field.name() == s)
+            .copied()
+            .ok_or_else(|| format!("unknown geoip field '{}'", s))
+    }
+}
+
+#[derive(Clone)]
+pub struct GeoIpFilter {
+    pub database_path: String,
+    pub fields: Vec<GeoIpField>,
+    // the database is loaded once, when the rules are parsed
+    reader: Arc<Reader<Vec<u8>>>,
+}
+
+impl fmt::Debug for GeoIpFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeoIpFilter")
+            .field("database_path", &self.database_path)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+/// Creates a `geoip(database_path, [field, ...])` filter, all fields are produced by default.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    let args = match f.args.as_deref() {
+        Some(args) if !args.is_empty() => args,
+        _ => return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    };
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            FunctionArgument::Arg(Value::Bytes(bytes)) => {
+                Ok(String::from_utf8_lossy(bytes).to_string())
+            }
+            _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let database_path = args[0].clone();
+    let fields = if args.len() == 1 {
+        ALL_FIELDS.to_vec()
+    } else {
+        args[1..]
+            .iter()
+            .map(|field| field.parse::<GeoIpField>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| GrokStaticError::InvalidFunctionArguments(f.name.clone()))?
+    };
+    let reader = Reader::open_readfile(&database_path).map_err(|error| {
+        error!(message = "Failed to load the geoip database", path = %database_path, %error);
+        GrokStaticError::InvalidFunctionArguments(f.name.clone())
+    })?;
+
+    Ok(GrokFilter::GeoIp(GeoIpFilter {
+        database_path,
+        fields,
+        reader: Arc::new(reader),
+    }))
+}
+
+/// Looks up an IP address in the database, producing an object with the requested fields that
+/// are known for this address. An address missing from the database produces an empty object.
+pub fn apply_filter(value: &Value, filter: &GeoIpFilter) -> Result<Value, GrokRuntimeError> {
+    let failed = || {
+        GrokRuntimeError::FailedToApplyFilter(
+            format!("GeoIp({})", filter.database_path),
+            value.to_string(),
+        )
+    };
+    let ip = match value {
+        Value::Bytes(bytes) => String::from_utf8_lossy(bytes)
+            .parse::<IpAddr>()
+            .map_err(|_| failed())?,
+        _ => return Err(failed()),
+    };
+
+    let mut result = BTreeMap::new();
+    let city = match filter.reader.lookup::<geoip2::City>(ip) {
+        Ok(city) => city,
+        Err(MaxMindDBError::AddressNotFoundError(_)) => return Ok(Value::Object(result)),
+        Err(_) => return Err(failed()),
+    };
+    for field in &filter.fields {
+        let value = match field {
+            GeoIpField::City => city
+                .city
+                .as_ref()
+                .and_then(|city| city.names.as_ref())
+                .and_then(|names| names.get("en"))
+                .map(|name| Value::from(*name)),
+            GeoIpField::Continent => city
+                .continent
+                .as_ref()
+                .and_then(|continent| continent.code)
+                .map(Value::from),
+            GeoIpField::Country => city
+                .country
+                .as_ref()
+                .and_then(|country| country.names.as_ref())
+                .and_then(|names| names.get("en"))
+                .map(|name| Value::from(*name)),
+            GeoIpField::CountryCode => city
+                .country
+                .as_ref()
+                .and_then(|country| country.iso_code)
+                .map(Value::from),
+            GeoIpField::Latitude => city
+                .location
+                .as_ref()
+                .and_then(|location| location.latitude)
+                .map(Value::from_f64_or_zero),
+            GeoIpField::Longitude => city
+                .location
+                .as_ref()
+                .and_then(|location| location.longitude)
+                .map(Value::from_f64_or_zero),
+            GeoIpField::Timezone => city
+                .location
+                .as_ref()
+                .and_then(|location| location.time_zone)
+                .map(Value::from),
+        };
+        if let Some(value) = value {
+            result.insert(field.name().to_string(), value);
+        }
+    }
+
+    Ok(Value::Object(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    const CITY_DATABASE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../tests/data/GeoIP2-City-Test.mmdb"
+    );
+
+    fn geoip_filter(fields: &[&str]) -> GeoIpFilter {
+        let args = std::iter::once(CITY_DATABASE)
+            .chain(fields.iter().copied())
+            .map(|arg| FunctionArgument::Arg(Value::from(arg)))
+            .collect();
+        match filter_from_function(&Function {
+            name: "geoip".to_string(),
+            args: Some(args),
+        }) {
+            Ok(GrokFilter::GeoIp(filter)) => filter,
+            _ => panic!("couldn't create the geoip filter"),
+        }
+    }
+
+    #[test]
+    fn looks_up_all_fields() {
+        assert_eq!(
+            apply_filter(&Value::from("2.125.160.216"), &geoip_filter(&[])),
+            Ok(Value::from(btreemap! {
+                "city" => "Boxford",
+                "continent" => "EU",
+                "country" => "United Kingdom",
+                "country_code" => "GB",
+                "latitude" => Value::from(51.75),
+                "longitude" => Value::from(-1.25),
+                "timezone" => "Europe/London",
+            }))
+        );
+    }
+
+    #[test]
+    fn looks_up_requested_fields() {
+        assert_eq!(
+            apply_filter(
+                &Value::from("2.125.160.216"),
+                &geoip_filter(&["country_code", "city"])
+            ),
+            Ok(Value::from(btreemap! {
+                "city" => "Boxford",
+                "country_code" => "GB",
+            }))
+        );
+    }
+
+    #[test]
+    fn produces_empty_object_on_lookup_miss() {
+        assert_eq!(
+            apply_filter(&Value::from("10.0.0.1"), &geoip_filter(&[])),
+            Ok(Value::from(BTreeMap::new()))
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_ip() {
+        assert!(apply_filter(&Value::from("not an ip"), &geoip_filter(&[])).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_databases() {
+        let function = |args: Vec<&str>| Function {
+            name: "geoip".to_string(),
+            args: Some(
+                args.into_iter()
+                    .map(|arg| FunctionArgument::Arg(Value::from(arg)))
+                    .collect(),
+            ),
+        };
+        assert!(filter_from_function(&function(vec![CITY_DATABASE, "zip"])).is_err());
+        assert!(filter_from_function(&function(vec!["/no/such/database.mmdb"])).is_err());
+    }
+}
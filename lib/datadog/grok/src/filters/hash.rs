@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+use sha3::Sha3_256;
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// A hash algorithm a value can be digested with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha3_256,
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "sha3" | "sha3-256" => Ok(HashAlgorithm::Sha3_256),
+            _ => Err(format!("unknown hash algorithm '{}'", s)),
+        }
+    }
+}
+
+/// Creates a `hash([algorithm])` filter, the algorithm defaults to sha256.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    match f.args.as_deref() {
+        None | Some([]) => Ok(GrokFilter::Hash(HashAlgorithm::Sha256)),
+        Some([FunctionArgument::Arg(Value::Bytes(algorithm))]) => {
+            String::from_utf8_lossy(algorithm)
+                .parse::<HashAlgorithm>()
+                .map(GrokFilter::Hash)
+                .map_err(|_| GrokStaticError::InvalidFunctionArguments(f.name.clone()))
+        }
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    }
+}
+
+/// Replaces a value with the lowercase hex digest of its bytes.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match (value, filter) {
+        (Value::Bytes(bytes), GrokFilter::Hash(algorithm)) => {
+            let digest = match algorithm {
+                HashAlgorithm::Sha256 => hex::encode(Sha256::digest(bytes)),
+                HashAlgorithm::Sha3_256 => hex::encode(Sha3_256::digest(bytes)),
+            };
+            Ok(digest.into())
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(input: &str, algorithm: HashAlgorithm) -> Result<Value, GrokRuntimeError> {
+        apply_filter(&Value::from(input), &GrokFilter::Hash(algorithm))
+    }
+
+    #[test]
+    fn computes_sha256_digest() {
+        assert_eq!(
+            hash("abc", HashAlgorithm::Sha256),
+            Ok(Value::from(
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            ))
+        );
+    }
+
+    #[test]
+    fn computes_sha3_256_digest() {
+        assert_eq!(
+            hash("abc", HashAlgorithm::Sha3_256),
+            Ok(Value::from(
+                "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+            ))
+        );
+    }
+
+    #[test]
+    fn fails_on_non_string_values() {
+        assert!(
+            apply_filter(&Value::Integer(1), &GrokFilter::Hash(HashAlgorithm::Sha256)).is_err()
+        );
+    }
+}
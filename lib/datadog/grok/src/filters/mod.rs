@@ -1,2 +1,10 @@
 pub mod array;
+pub mod bytesize;
+pub mod csv;
+pub mod duration;
+pub mod geoip;
+pub mod hash;
 pub mod keyvalue;
+pub mod querystring;
+pub mod rubyhash;
+pub mod xml;
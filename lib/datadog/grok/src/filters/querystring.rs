@@ -0,0 +1,126 @@
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use percent_encoding::percent_decode_str;
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// Creates a `querystring([valueless_as_true])` filter.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    match f.args.as_deref() {
+        None | Some([]) => Ok(GrokFilter::QueryString {
+            valueless_as_true: false,
+        }),
+        Some([FunctionArgument::Arg(Value::Boolean(valueless_as_true))]) => {
+            Ok(GrokFilter::QueryString {
+                valueless_as_true: *valueless_as_true,
+            })
+        }
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    }
+}
+
+/// Parses a query string, e.g. `?a=1&b=2&b=3&flag`, into an object.
+/// Keys and values are URL-decoded, values of repeated keys are collected into an array.
+/// Keys without a value are set to an empty string or, if `valueless_as_true` is set, to `true`.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match (value, filter) {
+        (Value::Bytes(bytes), GrokFilter::QueryString { valueless_as_true }) => {
+            let input = String::from_utf8_lossy(bytes);
+            let input = input.strip_prefix('?').unwrap_or(&input);
+
+            let mut result = BTreeMap::new();
+            for pair in input.split('&').filter(|pair| !pair.is_empty()) {
+                let (key, value) = match pair.split_once('=') {
+                    Some((key, value)) => (key, Value::from(decode(value))),
+                    None if *valueless_as_true => (pair, Value::Boolean(true)),
+                    None => (pair, Value::from("")),
+                };
+
+                match result.entry(decode(key)) {
+                    Entry::Occupied(mut entry) => match entry.get_mut() {
+                        Value::Array(values) => values.push(value),
+                        v => {
+                            let prev = std::mem::replace(v, Value::Null);
+                            *v = Value::Array(vec![prev, value]);
+                        }
+                    },
+                    Entry::Vacant(entry) => {
+                        entry.insert(value);
+                    }
+                }
+            }
+            Ok(result.into())
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+fn decode(input: &str) -> String {
+    percent_decode_str(&input.replace('+', " "))
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    fn query_string(input: &str, valueless_as_true: bool) -> Value {
+        apply_filter(
+            &Value::from(input),
+            &GrokFilter::QueryString { valueless_as_true },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn collects_repeated_keys() {
+        assert_eq!(
+            query_string("a=1&b=2&b=3&b=4", false),
+            Value::from(btreemap! {
+                "a" => "1",
+                "b" => Value::Array(vec!["2".into(), "3".into(), "4".into()]),
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_keys_and_values() {
+        assert_eq!(
+            query_string("?user%20name=John+Doe&path=%2Fhome%2Fjohn", false),
+            Value::from(btreemap! {
+                "user name" => "John Doe",
+                "path" => "/home/john",
+            })
+        );
+    }
+
+    #[test]
+    fn parses_valueless_keys() {
+        assert_eq!(
+            query_string("flag&a=", false),
+            Value::from(btreemap! {
+                "flag" => "",
+                "a" => "",
+            })
+        );
+        assert_eq!(
+            query_string("flag&a=", true),
+            Value::from(btreemap! {
+                "flag" => true,
+                "a" => "",
+            })
+        );
+    }
+}
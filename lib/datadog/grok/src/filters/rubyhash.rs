@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, multispace0},
+    combinator::{all_consuming, map, map_res, opt, value},
+    multi::separated_list0,
+    number::complete::recognize_float,
+    sequence::{delimited, preceded, separated_pair, terminated},
+    IResult,
+};
+use ordered_float::NotNan;
+use value::Value;
+
+use crate::{grok_filter::GrokFilter, parse_grok::Error as GrokRuntimeError};
+
+/// Parses a Ruby hash literal, e.g. `{:user=>1, "name"=>"bob", tags: [:a, "b"]}`, into an object.
+/// Symbol(`:foo`), string and `label:` keys are supported, values can be strings, symbols,
+/// numbers, `true`/`false`/`nil`, arrays and nested hashes.
+/// An input that is not a valid hash literal, or that nests hashes and arrays deeper than
+/// `MAX_DEPTH` levels, is returned unchanged.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    match value {
+        Value::Bytes(bytes) => {
+            Ok(parse(String::from_utf8_lossy(bytes).as_ref()).unwrap_or_else(|_| value.clone()))
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+/// The maximum nesting depth of hashes and arrays, which bounds the recursion of the parser.
+const MAX_DEPTH: usize = 64;
+
+type SResult<'a, O> = IResult<&'a str, O, (&'a str, nom::error::ErrorKind)>;
+
+fn parse(input: &str) -> Result<Value, String> {
+    all_consuming(delimited(
+        multispace0,
+        |input| parse_hash(input, 1),
+        multispace0,
+    ))(input)
+    .map(|(_, value)| value)
+    .map_err(|_| format!("could not parse '{}' as a ruby hash", input))
+}
+
+fn ws<'a, O, F>(parser: F) -> impl FnMut(&'a str) -> SResult<'a, O>
+where
+    F: FnMut(&'a str) -> SResult<'a, O>,
+{
+    delimited(multispace0, parser, multispace0)
+}
+
+/// Fails without backtracking once a hash or an array opens deeper than `MAX_DEPTH`.
+fn check_depth(input: &str, open: char, depth: usize) -> SResult<()> {
+    if depth > MAX_DEPTH && input.starts_with(open) {
+        Err(nom::Err::Failure((input, nom::error::ErrorKind::TooLarge)))
+    } else {
+        Ok((input, ()))
+    }
+}
+
+fn parse_hash(input: &str, depth: usize) -> SResult<Value> {
+    let (input, _) = check_depth(input, '{', depth)?;
+    map(
+        delimited(
+            terminated(char('{'), multispace0),
+            terminated(
+                separated_list0(ws(char(',')), |input| parse_pair(input, depth)),
+                opt(ws(char(','))),
+            ),
+            preceded(multispace0, char('}')),
+        ),
+        |pairs| Value::Object(pairs.into_iter().collect::<BTreeMap<_, _>>()),
+    )(input)
+}
+
+fn parse_array(input: &str, depth: usize) -> SResult<Value> {
+    let (input, _) = check_depth(input, '[', depth)?;
+    map(
+        delimited(
+            terminated(char('['), multispace0),
+            terminated(
+                separated_list0(ws(char(',')), |input| parse_value(input, depth)),
+                opt(ws(char(','))),
+            ),
+            preceded(multispace0, char(']')),
+        ),
+        Value::Array,
+    )(input)
+}
+
+fn parse_pair(input: &str, depth: usize) -> SResult<(String, Value)> {
+    alt((
+        separated_pair(parse_key, ws(tag("=>")), |input| parse_value(input, depth)),
+        // Ruby 1.9 style `key: value`
+        separated_pair(
+            map(take_while1(is_symbol_char), String::from),
+            terminated(char(':'), multispace0),
+            |input| parse_value(input, depth),
+        ),
+    ))(input)
+}
+
+fn parse_key(input: &str) -> SResult<String> {
+    alt((
+        parse_symbol,
+        parse_string,
+        map(recognize_float, String::from),
+    ))(input)
+}
+
+/// Parses the value of a hash or an array at a given depth, nested ones are one level deeper.
+fn parse_value(input: &str, depth: usize) -> SResult<Value> {
+    alt((
+        |input| parse_hash(input, depth + 1),
+        |input| parse_array(input, depth + 1),
+        map(parse_string, Value::from),
+        map(parse_symbol, Value::from),
+        parse_number,
+        value(Value::Boolean(true), tag("true")),
+        value(Value::Boolean(false), tag("false")),
+        value(Value::Null, tag("nil")),
+    ))(input)
+}
+
+fn parse_symbol(input: &str) -> SResult<String> {
+    preceded(
+        char(':'),
+        alt((parse_string, map(take_while1(is_symbol_char), String::from))),
+    )(input)
+}
+
+fn is_symbol_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '?' || c == '!'
+}
+
+fn parse_number(input: &str) -> SResult<Value> {
+    map_res(recognize_float, |s: &str| {
+        if s.contains(|c| c == '.' || c == 'e' || c == 'E') {
+            s.parse::<f64>()
+                .ok()
+                .and_then(|f| NotNan::new(f).ok())
+                .map(Value::Float)
+                .ok_or(())
+        } else {
+            s.parse::<i64>().map(Value::Integer).map_err(|_| ())
+        }
+    })(input)
+}
+
+fn parse_string(input: &str) -> SResult<String> {
+    alt((parse_quoted('"'), parse_quoted('\'')))(input)
+}
+
+fn parse_quoted(quote: char) -> impl Fn(&str) -> SResult<String> {
+    move |input| {
+        let (rest, _) = char(quote)(input)?;
+        let mut result = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) if quote == '"' => result.push('\n'),
+                    Some((_, 't')) if quote == '"' => result.push('\t'),
+                    Some((_, c)) => result.push(c),
+                    None => break,
+                },
+                c if c == quote => return Ok((&rest[i + c.len_utf8()..], result)),
+                c => result.push(c),
+            }
+        }
+        Err(nom::Err::Error((input, nom::error::ErrorKind::Char)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    #[test]
+    fn parses_nested_hashes_with_mixed_keys() {
+        let result = parse(
+            r#"{:user=>1, "name"=>"bob", 'role' => :admin, nested: {:ratio=>0.5, :tags=>["a", :b, nil], "active"=>true}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::from(btreemap! {
+                "user" => 1,
+                "name" => "bob",
+                "role" => "admin",
+                "nested" => btreemap! {
+                    "ratio" => 0.5,
+                    "tags" => Value::Array(vec!["a".into(), "b".into(), Value::Null]),
+                    "active" => true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn parses_escaped_strings() {
+        assert_eq!(
+            parse(r#"{"quote"=>"say \"hi\"", :empty=>{}}"#).unwrap(),
+            Value::from(btreemap! {
+                "quote" => r#"say "hi""#,
+                "empty" => Value::Object(BTreeMap::new()),
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_malformed_input() {
+        let value = Value::from(r#"{:user=>1, "name"=>}"#);
+        assert_eq!(apply_filter(&value, &GrokFilter::RubyHash).unwrap(), value);
+    }
+
+    #[test]
+    fn keeps_deeply_nested_input() {
+        let nested = |depth| format!("{{:a=>{}1{}}}", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&nested(MAX_DEPTH)).is_err());
+
+        let value = Value::from(nested(100_000));
+        assert_eq!(apply_filter(&value, &GrokFilter::RubyHash).unwrap(), value);
+    }
+}
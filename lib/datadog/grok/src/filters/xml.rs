@@ -0,0 +1,259 @@
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use roxmltree::{Document, Node};
+use value::Value;
+
+use crate::{
+    ast::{Function, FunctionArgument},
+    grok_filter::GrokFilter,
+    parse_grok::Error as GrokRuntimeError,
+    parse_grok_rules::Error as GrokStaticError,
+};
+
+/// The key under which text content is stored for elements that also have attributes or child elements.
+const TEXT_KEY: &str = "#text";
+/// The prefix used for attribute keys.
+const ATTRIBUTE_PREFIX: &str = "@";
+/// The key under which attributes are grouped when `xml(true)` is used.
+const ATTRIBUTES_KEY: &str = "@attributes";
+/// The maximum nesting depth of elements, deeper documents are kept unchanged as malformed ones.
+const MAX_DEPTH: usize = 64;
+
+/// Creates an `xml` filter from the function arguments: `xml([attributes_object])`.
+/// `xml(true)` groups attributes under an `@attributes` object and fails on malformed XML.
+pub fn filter_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    match f.args.as_deref() {
+        None | Some([]) => Ok(GrokFilter::Xml {
+            attributes_object: false,
+        }),
+        Some([FunctionArgument::Arg(Value::Boolean(attributes_object))]) => Ok(GrokFilter::Xml {
+            attributes_object: *attributes_object,
+        }),
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    }
+}
+
+/// Parses an XML document into an object: elements become keys, text content becomes values
+/// and attributes are stored with the `@` prefix, e.g.
+/// `<book category="fiction"><title>Dune</title></book>` is parsed to
+/// `{"book": {"@category": "fiction", "title": "Dune"}}`.
+/// Repeated elements are collected into an array.
+/// Malformed XML, as well as XML nested deeper than `MAX_DEPTH` elements, is not an error -
+/// the original value is returned unchanged.
+///
+/// With `attributes_object` set, attributes are stored without the prefix under an `@attributes`
+/// object instead, e.g. `{"book": {"@attributes": {"category": "fiction"}, "title": "Dune"}}`,
+/// and malformed or too deeply nested XML fails the filter.
+pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
+    let attributes_object = matches!(
+        filter,
+        GrokFilter::Xml {
+            attributes_object: true
+        }
+    );
+    match value {
+        Value::Bytes(bytes) => {
+            let input = String::from_utf8_lossy(bytes);
+            let parsed = Document::parse(&input).ok().and_then(|document| {
+                let root = document.root_element();
+                process_element(root, attributes_object, 1).map(|element| {
+                    let mut map = BTreeMap::new();
+                    map.insert(root.tag_name().name().to_string(), element);
+                    Value::from(map)
+                })
+            });
+            match parsed {
+                Some(parsed) => Ok(parsed),
+                None if attributes_object => Err(GrokRuntimeError::FailedToApplyFilter(
+                    filter.to_string(),
+                    value.to_string(),
+                )),
+                None => Ok(value.to_owned()),
+            }
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+/// Converts an element at a given depth, returns `None` if it is nested too deep.
+fn process_element(node: Node, attributes_object: bool, depth: usize) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let mut map = BTreeMap::new();
+    let mut text = String::new();
+
+    if attributes_object {
+        let attributes = node
+            .attributes()
+            .iter()
+            .map(|attr| (attr.name().to_string(), Value::from(attr.value())))
+            .collect::<BTreeMap<_, _>>();
+        if !attributes.is_empty() {
+            map.insert(ATTRIBUTES_KEY.to_string(), attributes.into());
+        }
+    } else {
+        for attr in node.attributes() {
+            map.insert(
+                format!("{}{}", ATTRIBUTE_PREFIX, attr.name()),
+                attr.value().into(),
+            );
+        }
+    }
+
+    for child in node.children() {
+        if child.is_element() {
+            let value = process_element(child, attributes_object, depth + 1)?;
+            match map.entry(child.tag_name().name().to_string()) {
+                Entry::Occupied(mut entry) => match entry.get_mut() {
+                    Value::Array(values) => values.push(value),
+                    v => {
+                        let prev = std::mem::replace(v, Value::Null);
+                        *v = Value::Array(vec![prev, value]);
+                    }
+                },
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        } else if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        }
+    }
+
+    let text = text.trim();
+    if map.is_empty() {
+        Some(text.into())
+    } else {
+        if !text.is_empty() {
+            map.insert(TEXT_KEY.to_string(), text.into());
+        }
+        Some(map.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    const XML: GrokFilter = GrokFilter::Xml {
+        attributes_object: false,
+    };
+    const XML_ATTRIBUTES_OBJECT: GrokFilter = GrokFilter::Xml {
+        attributes_object: true,
+    };
+
+    #[test]
+    fn parses_simple_element() {
+        assert_eq!(
+            apply_filter(&Value::from("<status>ok</status>"), &XML),
+            Ok(Value::from(btreemap! { "status" => "ok" }))
+        );
+    }
+
+    #[test]
+    fn parses_nested_elements() {
+        assert_eq!(
+            apply_filter(
+                &Value::from("<request><user><id>42</id><name>bob</name></user></request>"),
+                &XML
+            ),
+            Ok(Value::from(btreemap! {
+                "request" => btreemap! {
+                    "user" => btreemap! {
+                        "id" => "42",
+                        "name" => "bob",
+                    },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn parses_element_tree() {
+        let result = apply_filter(
+            &Value::from(
+                r#"<book category="fiction"><title lang="en">Dune</title><author>Frank Herbert</author><author>Brian Herbert</author></book>"#,
+            ),
+            &XML,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::from(btreemap! {
+                "book" => btreemap! {
+                    "@category" => "fiction",
+                    "title" => btreemap! {
+                        "@lang" => "en",
+                        "#text" => "Dune",
+                    },
+                    "author" => Value::Array(vec!["Frank Herbert".into(), "Brian Herbert".into()]),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_document() {
+        let value = Value::from("<book><title>Dune</book>");
+        assert_eq!(apply_filter(&value, &XML).unwrap(), value);
+    }
+
+    #[test]
+    fn keeps_deeply_nested_document() {
+        let nested = |depth| format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+        let value = Value::from(nested(MAX_DEPTH + 1));
+        assert_eq!(apply_filter(&value, &XML).unwrap(), value);
+
+        let parsed = apply_filter(&Value::from(nested(MAX_DEPTH)), &XML).unwrap();
+        assert!(parsed.is_object());
+    }
+
+    #[test]
+    fn groups_attributes_into_object() {
+        let result = apply_filter(
+            &Value::from(
+                r#"<book category="fiction" year="1965"><title lang="en">Dune</title><author>Frank Herbert</author></book>"#,
+            ),
+            &XML_ATTRIBUTES_OBJECT,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::from(btreemap! {
+                "book" => btreemap! {
+                    "@attributes" => btreemap! {
+                        "category" => "fiction",
+                        "year" => "1965",
+                    },
+                    "title" => btreemap! {
+                        "@attributes" => btreemap! {
+                            "lang" => "en",
+                        },
+                        "#text" => "Dune",
+                    },
+                    "author" => "Frank Herbert",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_document_with_attributes_object() {
+        let value = Value::from("<book><title>Dune</book>");
+        assert!(apply_filter(&value, &XML_ATTRIBUTES_OBJECT).is_err());
+
+        let nested = format!(
+            "{}x{}",
+            "<a>".repeat(MAX_DEPTH + 1),
+            "</a>".repeat(MAX_DEPTH + 1)
+        );
+        assert!(apply_filter(&Value::from(nested), &XML_ATTRIBUTES_OBJECT).is_err());
+    }
+}
@@ -8,7 +8,7 @@
 
 include!(concat!(env!("OUT_DIR"), "/patterns.rs"));
 
-use std::collections::{btree_map, BTreeMap};
+use std::collections::{btree_map, BTreeMap, HashMap};
 use std::sync::Arc;
 
 use onig::{Captures, Regex};
@@ -44,6 +44,16 @@ impl<'a> Matches<'a> {
             names: self.names.iter(),
         }
     }
+
+    /// Collects all the matches into a map of names to matched values.
+    ///
+    /// Note that if no match is found, the value is empty.
+    pub fn into_map(self) -> HashMap<&'a str, &'a str> {
+        self.names
+            .iter()
+            .map(|(k, v)| (k.as_str(), self.captures.at(*v as usize).unwrap_or("")))
+            .collect()
+    }
 }
 
 pub struct MatchesIter<'a> {
@@ -105,6 +115,17 @@ impl Pattern {
         }
     }
 
+    /// Returns the number of capture groups of this compiled `Pattern`.
+    pub fn capture_count(&self) -> usize {
+        self.regex.captures_len()
+    }
+
+    /// Returns whether this compiled `Pattern` matches the text, without extracting the matches.
+    #[inline]
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.find(text).is_some()
+    }
+
     /// Matches this compiled `Pattern` against the text and returns the matches.
     #[inline]
     pub fn match_against<'a>(&'a self, text: &'a str) -> Option<Matches<'a>> {
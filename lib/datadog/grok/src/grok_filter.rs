@@ -1,4 +1,4 @@
-use std::{convert::TryFrom, string::ToString};
+use std::{collections::BTreeMap, convert::TryFrom, net::IpAddr, string::ToString};
 
 use ordered_float::NotNan;
 use strum_macros::Display;
@@ -6,7 +6,15 @@ use value::Value;
 
 use crate::{
     ast::{Function, FunctionArgument},
-    filters::{array, keyvalue, keyvalue::KeyValueFilter},
+    filters::{
+        array, bytesize, csv,
+        duration::{self, DurationUnit},
+        geoip::{self, GeoIpFilter},
+        hash::{self, HashAlgorithm},
+        keyvalue,
+        keyvalue::KeyValueFilter,
+        querystring, rubyhash, xml,
+    },
     matchers::date::{apply_date_filter, DateFilter},
     parse_grok::Error as GrokRuntimeError,
     parse_grok_rules::Error as GrokStaticError,
@@ -22,6 +30,8 @@ pub enum GrokFilter {
     NumberExt,
     // with scientific notation support, e.g. 1.52e10
     NullIf(String),
+    // removes the field if the value fully matches the regex, unlike nullIf, which sets it to null
+    NullIfMatches(regex::Regex),
     Scale(f64),
     Lowercase,
     Uppercase,
@@ -32,6 +42,149 @@ pub enum GrokFilter {
         Box<Option<GrokFilter>>,
     ),
     KeyValue(KeyValueFilter),
+    Csv {
+        delimiter: u8,
+        headers: Option<Vec<String>>,
+    },
+    Xml {
+        // groups attributes under an `@attributes` object instead of prefixing them with `@`
+        attributes_object: bool,
+    },
+    RubyHash,
+    // trims the given characters, whitespace by default
+    Trim(Option<String>),
+    TrimLeft(Option<String>),
+    TrimRight(Option<String>),
+    // replaces a null or an empty value with a default
+    DefaultTo(Value),
+    EmptyStringIfNull,
+    // a number with grouping separators, e.g. 1,234,567.89 or 1.234.567,89
+    NumberWithGrouping {
+        grouping: char,
+        decimal: char,
+    },
+    // validates and normalizes an IPv4 or IPv6 address
+    Ip {
+        with_version: bool,
+        strict: bool,
+    },
+    QueryString {
+        valueless_as_true: bool,
+    },
+    // a duration with a unit suffix, e.g. 1.5s, converted to an integer in the given unit
+    Duration {
+        output_unit: DurationUnit,
+    },
+    // decodes a base64 string, keeps an invalid one as is unless strict
+    DecodeBase64 {
+        url_safe: bool,
+        strict: bool,
+    },
+    // splits a string by a delimiter, skipping empty segments, and optionally picks a single segment
+    Split {
+        delimiter: String,
+        index: Option<usize>,
+    },
+    // a human-readable size, e.g. 1.5MiB, in bytes
+    ByteSize,
+    // strips the surrounding quotes of a quoted string and unescapes backslash escapes
+    Unquote,
+    // replaces a value with its hex digest, e.g. to correlate values without keeping them
+    Hash(HashAlgorithm),
+    // looks up an IP address in a MaxMind City database
+    GeoIp(GeoIpFilter),
+    // decomposes a semantic version, e.g. 1.2.3-rc.1+build, keeps an invalid one as is
+    Semver,
+}
+
+/// The kind of values filters accept and produce, used to validate filter chains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterValueKind {
+    String,
+    // a string or null, e.g. of nullIf
+    NullableString,
+    Number,
+    // epoch milliseconds of a date, which are only meant to be stored
+    Timestamp,
+    // objects and arrays
+    Structured,
+    Any,
+}
+
+impl GrokFilter {
+    /// Whether this filter should be applied to an empty match, e.g. of an alternative that did not match.
+    pub fn applies_to_empty(&self) -> bool {
+        matches!(self, GrokFilter::DefaultTo(_))
+    }
+
+    /// Whether this filter can be applied to a value of a given kind.
+    fn accepts(&self, kind: FilterValueKind) -> bool {
+        match self {
+            GrokFilter::DefaultTo(_) | GrokFilter::EmptyStringIfNull => true,
+            GrokFilter::Integer
+            | GrokFilter::IntegerExt
+            | GrokFilter::Number
+            | GrokFilter::NumberExt
+            | GrokFilter::Scale(_) => matches!(
+                kind,
+                FilterValueKind::String | FilterValueKind::Number | FilterValueKind::Any
+            ),
+            _ => matches!(kind, FilterValueKind::String | FilterValueKind::Any),
+        }
+    }
+
+    /// The kind of values this filter produces.
+    fn output_kind(&self) -> FilterValueKind {
+        match self {
+            GrokFilter::Integer
+            | GrokFilter::IntegerExt
+            | GrokFilter::Number
+            | GrokFilter::NumberExt
+            | GrokFilter::Scale(_)
+            | GrokFilter::NumberWithGrouping { .. }
+            | GrokFilter::Duration { .. }
+            | GrokFilter::ByteSize => FilterValueKind::Number,
+            GrokFilter::Array(..)
+            | GrokFilter::KeyValue(_)
+            | GrokFilter::Csv { .. }
+            | GrokFilter::Xml { .. }
+            | GrokFilter::RubyHash
+            | GrokFilter::QueryString { .. }
+            | GrokFilter::GeoIp(_)
+            | GrokFilter::Split { index: None, .. } => FilterValueKind::Structured,
+            GrokFilter::Json
+            | GrokFilter::DefaultTo(_)
+            | GrokFilter::EmptyStringIfNull
+            | GrokFilter::Semver => FilterValueKind::Any,
+            GrokFilter::Date(_) => FilterValueKind::Timestamp,
+            GrokFilter::NullIf(_) | GrokFilter::NullIfMatches(_) => FilterValueKind::NullableString,
+            GrokFilter::Lowercase
+            | GrokFilter::Uppercase
+            | GrokFilter::Trim(_)
+            | GrokFilter::TrimLeft(_)
+            | GrokFilter::TrimRight(_)
+            | GrokFilter::Ip { .. }
+            | GrokFilter::DecodeBase64 { .. }
+            | GrokFilter::Split { index: Some(_), .. }
+            | GrokFilter::Unquote
+            | GrokFilter::Hash(_) => FilterValueKind::String,
+        }
+    }
+}
+
+/// Checks that each filter in a chain can be applied to the output of the previous one,
+/// e.g. `uppercase` can't be applied to the output of `integer`.
+pub fn validate_filter_chain(filters: &[GrokFilter]) -> Result<(), GrokStaticError> {
+    filters.windows(2).try_for_each(|pair| {
+        if pair[1].accepts(pair[0].output_kind()) {
+            Ok(())
+        } else {
+            Err(GrokStaticError::IncompatibleFilters(
+                pair[0].to_string(),
+                pair[1].to_string(),
+            ))
+        }
+    })
 }
 
 impl TryFrom<&Function> for GrokFilter {
@@ -72,18 +225,263 @@ impl TryFrom<&Function> for GrokFilter {
                     }
                 })
                 .ok_or_else(|| GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            "nullIfMatches" => match f.args.as_deref() {
+                Some([FunctionArgument::Arg(Value::Bytes(pattern))]) => {
+                    regex::Regex::new(&format!("^(?:{})$", String::from_utf8_lossy(pattern)))
+                        .map(GrokFilter::NullIfMatches)
+                        .map_err(|_| GrokStaticError::InvalidFunctionArguments(f.name.clone()))
+                }
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            },
             "array" => array::filter_from_function(f),
             "keyvalue" => keyvalue::filter_from_function(f),
+            "csv" => csv::filter_from_function(f),
+            "xml" => xml::filter_from_function(f),
+            "rubyhash" => Ok(GrokFilter::RubyHash),
+            "trim" => trim_chars_from_function(f).map(GrokFilter::Trim),
+            "trimLeft" => trim_chars_from_function(f).map(GrokFilter::TrimLeft),
+            "trimRight" => trim_chars_from_function(f).map(GrokFilter::TrimRight),
+            "defaultTo" => match f.args.as_deref() {
+                Some([FunctionArgument::Arg(default)]) => {
+                    Ok(GrokFilter::DefaultTo(default.clone()))
+                }
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            },
+            "emptyStringIfNull" => Ok(GrokFilter::EmptyStringIfNull),
+            "numberWithGrouping" => number_with_grouping_from_function(f),
+            "querystring" => querystring::filter_from_function(f),
+            "duration" => duration::filter_from_function(f),
+            "byteSize" => Ok(GrokFilter::ByteSize),
+            "hash" => hash::filter_from_function(f),
+            "geoip" => geoip::filter_from_function(f),
+            "semver" => Ok(GrokFilter::Semver),
+            "split" => match f.args.as_deref() {
+                Some([FunctionArgument::Arg(Value::Bytes(delimiter))]) if !delimiter.is_empty() => {
+                    Ok(GrokFilter::Split {
+                        delimiter: String::from_utf8_lossy(delimiter).to_string(),
+                        index: None,
+                    })
+                }
+                Some(
+                    [FunctionArgument::Arg(Value::Bytes(delimiter)), FunctionArgument::Arg(Value::Integer(index))],
+                ) if !delimiter.is_empty() && *index >= 0 => Ok(GrokFilter::Split {
+                    delimiter: String::from_utf8_lossy(delimiter).to_string(),
+                    index: Some(*index as usize),
+                }),
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            },
+            "decodeBase64" => match f.args.as_deref() {
+                None | Some([]) => Ok(GrokFilter::DecodeBase64 {
+                    url_safe: false,
+                    strict: false,
+                }),
+                Some([FunctionArgument::Arg(Value::Boolean(url_safe))]) => {
+                    Ok(GrokFilter::DecodeBase64 {
+                        url_safe: *url_safe,
+                        strict: false,
+                    })
+                }
+                Some(
+                    [FunctionArgument::Arg(Value::Boolean(url_safe)), FunctionArgument::Arg(Value::Boolean(strict))],
+                ) => Ok(GrokFilter::DecodeBase64 {
+                    url_safe: *url_safe,
+                    strict: *strict,
+                }),
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            },
+            "ip" => match f.args.as_deref() {
+                None | Some([]) => Ok(GrokFilter::Ip {
+                    with_version: false,
+                    strict: false,
+                }),
+                Some([FunctionArgument::Arg(Value::Boolean(with_version))]) => Ok(GrokFilter::Ip {
+                    with_version: *with_version,
+                    strict: false,
+                }),
+                Some(
+                    [FunctionArgument::Arg(Value::Boolean(with_version)), FunctionArgument::Arg(Value::Boolean(strict))],
+                ) => Ok(GrokFilter::Ip {
+                    with_version: *with_version,
+                    strict: *strict,
+                }),
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            },
             _ => Err(GrokStaticError::UnknownFilter(f.name.clone())),
         }
     }
 }
 
+/// Extracts an optional set of characters to trim, e.g. `trim("\"'")`.
+fn trim_chars_from_function(f: &Function) -> Result<Option<String>, GrokStaticError> {
+    match f.args.as_deref() {
+        None | Some([]) => Ok(None),
+        Some([FunctionArgument::Arg(Value::Bytes(chars))]) => {
+            Ok(Some(String::from_utf8_lossy(chars).to_string()))
+        }
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    }
+}
+
+/// Creates a `numberWithGrouping([grouping[, decimal]])` filter, separators default to `,` and `.`.
+fn number_with_grouping_from_function(f: &Function) -> Result<GrokFilter, GrokStaticError> {
+    let separator = |arg: Option<&FunctionArgument>, default: char| match arg {
+        None => Ok(default),
+        Some(FunctionArgument::Arg(Value::Bytes(bytes))) => {
+            let separator = String::from_utf8_lossy(bytes);
+            let mut chars = separator.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+            }
+        }
+        _ => Err(GrokStaticError::InvalidFunctionArguments(f.name.clone())),
+    };
+    let args = f.args.as_deref().unwrap_or_default();
+    if args.len() > 2 {
+        return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone()));
+    }
+    let grouping = separator(args.get(0), ',')?;
+    let decimal = separator(args.get(1), '.')?;
+    if grouping == decimal {
+        return Err(GrokStaticError::InvalidFunctionArguments(f.name.clone()));
+    }
+    Ok(GrokFilter::NumberWithGrouping { grouping, decimal })
+}
+
+/// Trims characters from a string.
+fn trim(value: &str, chars: &Option<String>, left: bool, right: bool) -> String {
+    let is_trimmed = |c: char| match chars {
+        Some(chars) => chars.contains(c),
+        None => c.is_whitespace(),
+    };
+    let value = if left {
+        value.trim_start_matches(is_trimmed)
+    } else {
+        value
+    };
+    let value = if right {
+        value.trim_end_matches(is_trimmed)
+    } else {
+        value
+    };
+    value.to_string()
+}
+
+/// Strips the surrounding quotes(double or single) and unescapes backslash escapes, e.g. `"say \"hi\""` -> `say "hi"`.
+fn unquote(value: &str) -> String {
+    let inner = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) if value.len() >= 2 && value.ends_with(quote) => {
+            &value[1..value.len() - 1]
+        }
+        _ => value,
+    };
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// The output of a filter applied to a field: the new value of the field, or `None` if the field
+/// has to be removed, and the values of its companion fields, which are stored next to it as
+/// `<field>_<suffix>`, e.g. `client_ip_version`.
+#[derive(Debug, PartialEq)]
+pub struct FilterOutput {
+    pub value: Option<Value>,
+    pub companions: Vec<(&'static str, Value)>,
+}
+
+impl From<Value> for FilterOutput {
+    fn from(value: Value) -> Self {
+        Self {
+            value: Some(value),
+            companions: vec![],
+        }
+    }
+}
+
+/// Same as `apply_filter`, but for the value of a field: the filter can also remove the field or
+/// produce companion fields.
+pub fn apply_filter_to_field(
+    value: &Value,
+    filter: &GrokFilter,
+) -> Result<FilterOutput, GrokRuntimeError> {
+    match filter {
+        GrokFilter::NullIfMatches(_) => apply_null_if_matches_filter(value, filter),
+        GrokFilter::Ip { .. } => apply_ip_filter(value, filter),
+        _ => apply_filter(value, filter).map(FilterOutput::from),
+    }
+}
+
+/// Removes a value, that fully matches the regex.
+fn apply_null_if_matches_filter(
+    value: &Value,
+    filter: &GrokFilter,
+) -> Result<FilterOutput, GrokRuntimeError> {
+    match (value, filter) {
+        (Value::Bytes(bytes), GrokFilter::NullIfMatches(regex)) => {
+            if regex.is_match(&String::from_utf8_lossy(bytes)) {
+                Ok(FilterOutput {
+                    value: None,
+                    companions: vec![],
+                })
+            } else {
+                Ok(value.to_owned().into())
+            }
+        }
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
+/// Normalizes an IP address, e.g. compresses and lowercases IPv6 addresses, optionally with its
+/// version(4 or 6) in the `version` companion field.
+fn apply_ip_filter(value: &Value, filter: &GrokFilter) -> Result<FilterOutput, GrokRuntimeError> {
+    let (with_version, strict) = match filter {
+        GrokFilter::Ip {
+            with_version,
+            strict,
+        } => (*with_version, *strict),
+        _ => unreachable!("not an ip filter"),
+    };
+    match value {
+        Value::Bytes(bytes) => match String::from_utf8_lossy(bytes).parse::<IpAddr>() {
+            Ok(ip) => {
+                let version = if ip.is_ipv4() { 4 } else { 6 };
+                Ok(FilterOutput {
+                    value: Some(ip.to_string().into()),
+                    companions: if with_version {
+                        vec![("version", version.into())]
+                    } else {
+                        vec![]
+                    },
+                })
+            }
+            Err(_) if strict => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+            Err(_) => Ok(value.to_owned().into()),
+        },
+        _ => Err(GrokRuntimeError::FailedToApplyFilter(
+            filter.to_string(),
+            value.to_string(),
+        )),
+    }
+}
+
 /// Applies a given Grok filter to the value and returns the result or error.
 /// For detailed description and examples of specific filters check out https://docs.datadoghq.com/logs/log_configuration/parsing/?tab=filters
 pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRuntimeError> {
     match filter {
         GrokFilter::Integer => match value {
+            Value::Integer(_) => Ok(value.to_owned()),
             Value::Bytes(v) => Ok(String::from_utf8_lossy(v)
                 .parse::<i64>()
                 .map_err(|_e| {
@@ -96,6 +494,9 @@ pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRun
             )),
         },
         GrokFilter::IntegerExt => match value {
+            Value::Integer(_) => Ok(value.to_owned()),
+            // truncated, as a string with a fraction is
+            Value::Float(v) => Ok((v.into_inner() as i64).into()),
             Value::Bytes(v) => Ok(String::from_utf8_lossy(v)
                 .parse::<f64>()
                 .map_err(|_e| {
@@ -108,6 +509,7 @@ pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRun
             )),
         },
         GrokFilter::Number | GrokFilter::NumberExt => match value {
+            Value::Integer(_) | Value::Float(_) => Ok(value.to_owned()),
             Value::Bytes(v) => {
                 let v = Ok(Value::from_f64_or_zero(
                     String::from_utf8_lossy(v).parse::<f64>().map_err(|_e| {
@@ -190,8 +592,143 @@ pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRun
                 value.to_string(),
             )),
         },
+        // there is no field to remove here, e.g. for the elements of an array, so the value is nulled
+        GrokFilter::NullIfMatches(_) => apply_null_if_matches_filter(value, filter)
+            .map(|output| output.value.unwrap_or(Value::Null)),
         GrokFilter::Date(date_filter) => apply_date_filter(value, date_filter),
         GrokFilter::KeyValue(keyvalue_filter) => keyvalue::apply_filter(value, keyvalue_filter),
+        GrokFilter::Csv { .. } => csv::apply_filter(value, filter),
+        GrokFilter::Xml { .. } => xml::apply_filter(value, filter),
+        GrokFilter::RubyHash => rubyhash::apply_filter(value, filter),
+        GrokFilter::QueryString { .. } => querystring::apply_filter(value, filter),
+        GrokFilter::Duration { .. } => duration::apply_filter(value, filter),
+        GrokFilter::ByteSize => bytesize::apply_filter(value, filter),
+        GrokFilter::Hash(_) => hash::apply_filter(value, filter),
+        GrokFilter::GeoIp(geoip_filter) => geoip::apply_filter(value, geoip_filter),
+        GrokFilter::Split { delimiter, index } => match value {
+            Value::Bytes(bytes) => {
+                let value_str = String::from_utf8_lossy(bytes);
+                let mut segments = value_str
+                    .split(delimiter.as_str())
+                    .filter(|segment| !segment.is_empty());
+                match index {
+                    Some(index) => segments.nth(*index).map(Value::from).ok_or_else(|| {
+                        GrokRuntimeError::FailedToApplyFilter(filter.to_string(), value.to_string())
+                    }),
+                    None => Ok(segments.map(Value::from).collect::<Vec<_>>().into()),
+                }
+            }
+            _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+        },
+        GrokFilter::Semver => match value {
+            Value::Bytes(bytes) => {
+                match semver::Version::parse(String::from_utf8_lossy(bytes).trim()) {
+                    Ok(version) => {
+                        let mut result = BTreeMap::new();
+                        result.insert("major".to_string(), Value::Integer(version.major as i64));
+                        result.insert("minor".to_string(), Value::Integer(version.minor as i64));
+                        result.insert("patch".to_string(), Value::Integer(version.patch as i64));
+                        if !version.pre.is_empty() {
+                            result.insert("prerelease".to_string(), version.pre.as_str().into());
+                        }
+                        if !version.build.is_empty() {
+                            result.insert("build".to_string(), version.build.as_str().into());
+                        }
+                        Ok(result.into())
+                    }
+                    Err(_) => Ok(value.to_owned()),
+                }
+            }
+            _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+        },
+        GrokFilter::DecodeBase64 { url_safe, strict } => match value {
+            Value::Bytes(bytes) => {
+                let config = if *url_safe {
+                    base64::URL_SAFE
+                } else {
+                    base64::STANDARD
+                };
+                match base64::decode_config(bytes, config) {
+                    Ok(decoded) => Ok(Value::Bytes(decoded.into())),
+                    Err(_) if *strict => Err(GrokRuntimeError::FailedToApplyFilter(
+                        filter.to_string(),
+                        value.to_string(),
+                    )),
+                    Err(_) => Ok(value.to_owned()),
+                }
+            }
+            _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+        },
+        GrokFilter::Unquote => match value {
+            Value::Bytes(bytes) => Ok(unquote(String::from_utf8_lossy(bytes).as_ref()).into()),
+            _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+        },
+        GrokFilter::NumberWithGrouping { grouping, decimal } => match value {
+            Value::Bytes(bytes) => {
+                let normalized = String::from_utf8_lossy(bytes)
+                    .chars()
+                    .filter(|c| c != grouping)
+                    .map(|c| if c == *decimal { '.' } else { c })
+                    .collect::<String>();
+                if normalized.contains('.') {
+                    normalized
+                        .parse::<f64>()
+                        .map(Value::from_f64_or_zero)
+                        .map_err(|_e| {
+                            GrokRuntimeError::FailedToApplyFilter(
+                                filter.to_string(),
+                                value.to_string(),
+                            )
+                        })
+                } else {
+                    normalized.parse::<i64>().map(Value::from).map_err(|_e| {
+                        GrokRuntimeError::FailedToApplyFilter(filter.to_string(), value.to_string())
+                    })
+                }
+            }
+            _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                filter.to_string(),
+                value.to_string(),
+            )),
+        },
+        GrokFilter::Ip { .. } => apply_ip_filter(value, filter)
+            .map(|output| output.value.expect("the ip filter keeps the value")),
+        GrokFilter::DefaultTo(default) => match value {
+            Value::Null => Ok(default.clone()),
+            Value::Bytes(bytes) if bytes.is_empty() => Ok(default.clone()),
+            _ => Ok(value.to_owned()),
+        },
+        GrokFilter::EmptyStringIfNull => match value {
+            Value::Null => Ok("".into()),
+            _ => Ok(value.to_owned()),
+        },
+        GrokFilter::Trim(chars) | GrokFilter::TrimLeft(chars) | GrokFilter::TrimRight(chars) => {
+            match value {
+                Value::Bytes(bytes) => Ok(trim(
+                    String::from_utf8_lossy(bytes).as_ref(),
+                    chars,
+                    !matches!(filter, GrokFilter::TrimRight(_)),
+                    !matches!(filter, GrokFilter::TrimLeft(_)),
+                )
+                .into()),
+                _ => Err(GrokRuntimeError::FailedToApplyFilter(
+                    filter.to_string(),
+                    value.to_string(),
+                )),
+            }
+        }
         GrokFilter::Array(brackets, delimiter, value_filter) => match value {
             Value::Bytes(bytes) => array::parse(
                 String::from_utf8_lossy(bytes).as_ref(),
@@ -221,3 +758,120 @@ pub fn apply_filter(value: &Value, filter: &GrokFilter) -> Result<Value, GrokRun
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_null_with_empty_string() {
+        assert_eq!(
+            apply_filter(&Value::Null, &GrokFilter::EmptyStringIfNull).unwrap(),
+            Value::from("")
+        );
+        assert_eq!(
+            apply_filter(&Value::from("abc"), &GrokFilter::EmptyStringIfNull).unwrap(),
+            Value::from("abc")
+        );
+    }
+
+    #[test]
+    fn replaces_null_with_default() {
+        let filter = GrokFilter::DefaultTo("unknown".into());
+        assert_eq!(
+            apply_filter(&Value::Null, &filter).unwrap(),
+            Value::from("unknown")
+        );
+        assert_eq!(
+            apply_filter(&Value::from("abc"), &filter).unwrap(),
+            Value::from("abc")
+        );
+    }
+
+    #[test]
+    fn replaces_nulled_value_with_default() {
+        // the grammar takes a single explicit filter per capture, so this chain can't be written
+        // in a rule, it only shows that the output of nullIf is a valid input of defaultTo
+        let value = apply_filter(&Value::from("-"), &GrokFilter::NullIf("-".to_string())).unwrap();
+        assert_eq!(
+            apply_filter(&value, &GrokFilter::DefaultTo("unknown".into())).unwrap(),
+            Value::from("unknown")
+        );
+    }
+
+    #[test]
+    fn returns_ip_version_companion() {
+        let ip = |with_version| GrokFilter::Ip {
+            with_version,
+            strict: false,
+        };
+        assert_eq!(
+            apply_filter_to_field(&Value::from("2001:DB8::0:1"), &ip(true)).unwrap(),
+            FilterOutput {
+                value: "2001:db8::1".into(),
+                companions: vec![("version", 6.into())],
+            }
+        );
+        assert_eq!(
+            apply_filter_to_field(&Value::from("10.0.0.1"), &ip(false)).unwrap(),
+            Value::from("10.0.0.1").into()
+        );
+        // an invalid address has no version
+        assert_eq!(
+            apply_filter_to_field(&Value::from("10.0.0"), &ip(true)).unwrap(),
+            Value::from("10.0.0").into()
+        );
+    }
+
+    #[test]
+    fn removes_field_matching_regex() {
+        let filter = GrokFilter::NullIfMatches(regex::Regex::new("^(?:N/A|-)$").unwrap());
+        assert_eq!(
+            apply_filter_to_field(&Value::from("N/A"), &filter).unwrap(),
+            FilterOutput {
+                value: None,
+                companions: vec![],
+            }
+        );
+        assert_eq!(
+            apply_filter_to_field(&Value::from("abc"), &filter).unwrap(),
+            Value::from("abc").into()
+        );
+        // without a field to remove, the value is nulled
+        assert_eq!(
+            apply_filter(&Value::from("-"), &filter).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn validates_nullable_filter_chains() {
+        let null_if = GrokFilter::NullIf("-".to_string());
+        assert!(
+            validate_filter_chain(&[null_if.clone(), GrokFilter::DefaultTo("unknown".into())])
+                .is_ok()
+        );
+        assert!(validate_filter_chain(&[null_if.clone(), GrokFilter::EmptyStringIfNull]).is_ok());
+        assert_eq!(
+            validate_filter_chain(&[null_if, GrokFilter::Uppercase]),
+            Err(GrokStaticError::IncompatibleFilters(
+                "NullIf".to_string(),
+                "Uppercase".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn validates_date_filter_chains() {
+        let date = GrokFilter::Date(DateFilter {
+            original_format: "HH:mm".to_string(),
+            strp_format: "%H:%M".to_string(),
+            tz_aware: false,
+            regex_with_tz: None,
+            target_tz: None,
+        });
+        assert!(validate_filter_chain(&[date.clone(), GrokFilter::DefaultTo(0.into())]).is_ok());
+        assert!(validate_filter_chain(&[date.clone(), GrokFilter::Scale(0.001)]).is_err());
+        assert!(validate_filter_chain(&[date, GrokFilter::Integer]).is_err());
+    }
+}
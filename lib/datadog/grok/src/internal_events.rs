@@ -0,0 +1,38 @@
+//! Events of the `internal-events` feature. When parsing runs within a component, e.g. the `remap`
+//! transform, its counters get the component tags from the current span, as other internal events.
+
+use metrics::counter;
+use tracing::trace;
+use vector_common::internal_event::InternalEvent;
+
+/// A value is matched by the rule with a given index, counted per rule index.
+#[derive(Debug)]
+pub struct GrokRuleMatched {
+    pub rule_index: usize,
+}
+
+impl InternalEvent for GrokRuleMatched {
+    fn emit(self) {
+        trace!(message = "Grok rule matched.", rule_index = %self.rule_index);
+        counter!("grok_matched_events_total", 1, "rule_index" => self.rule_index.to_string());
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("GrokRuleMatched")
+    }
+}
+
+/// A value is matched by none of the rules.
+#[derive(Debug)]
+pub struct GrokNoRuleMatched;
+
+impl InternalEvent for GrokNoRuleMatched {
+    fn emit(self) {
+        trace!(message = "No grok rule matched.");
+        counter!("grok_unmatched_events_total", 1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("GrokNoRuleMatched")
+    }
+}
@@ -5,10 +5,13 @@
 #![deny(unused_comparisons)]
 
 mod ast;
+pub mod checksum;
 #[doc(hidden)]
 pub mod filters; // TODO Must be exposed for criterion. Perhaps we should pass a feature? Yuck.
 mod grok;
 mod grok_filter;
+#[cfg(feature = "internal-events")]
+mod internal_events;
 mod lexer;
 mod matchers;
 pub mod parse_grok;
@@ -31,14 +31,21 @@ pub fn convert_time_format(format: &str) -> std::result::Result<String, String>
                         time_format.pop(); // drop .
                         time_format.push_str("%.f");
                     } else {
-                        time_format.push_str("%f");
+                        // fixed width fractions, e.g. with a comma: HH:mm:ss,SSS
+                        match token.len() {
+                            3 => time_format.push_str("%3f"),
+                            6 => time_format.push_str("%6f"),
+                            9 => time_format.push_str("%9f"),
+                            _ => time_format.push_str("%f"),
+                        }
                     }
                 }
                 // year
                 'y' | 'Y' if token.len() == 2 => time_format.push_str("%y"),
                 'y' | 'Y' => time_format.push_str("%Y"),
-                // weekyear
-                'x' => time_format.push_str("%D"),
+                // epoch milliseconds(x) or seconds(X), only supported as a standalone format:
+                // strptime has no epoch milliseconds specifier, see `apply_date_filter`
+                'x' | 'X' if format.len() == 1 => time_format.push_str("%s"),
                 // century
                 'c' | 'C' => time_format.push_str("%C"),
                 // day
@@ -75,6 +82,9 @@ pub fn convert_time_format(format: &str) -> std::result::Result<String, String>
                         time_format.push_str("%z");
                     } else if token.len() == 2 {
                         time_format.push_str("%:z");
+                    } else {
+                        // time zone id, e.g. Europe/Paris
+                        time_format.push_str("%Z");
                     }
                 }
                 _ => return Err(format!("invalid date format '{}'", format)),
@@ -144,9 +154,11 @@ pub fn time_format_to_regex(
         if ('A'..='Z').contains(&c) || ('a'..='z').contains(&c) {
             let token: String = chars.by_ref().peeking_take_while(|&cn| cn == c).collect();
             match token.chars().next().unwrap() {
-                'h' | 'H' | 'm' | 's' | 'S' | 'Y' | 'x' | 'c' | 'C' | 'e' | 'D' | 'w' => {
+                'h' | 'H' | 'm' | 's' | 'S' | 'Y' | 'c' | 'C' | 'e' | 'D' | 'w' => {
                     regex.push_str(format!("[\\d]{{{}}}", token.len()).as_str())
                 }
+                // epoch milliseconds or seconds, only supported as a standalone format
+                'x' | 'X' if format.len() == 1 => regex.push_str("[\\d]+"),
                 // days
                 'd' if token.len() == 1 => regex.push_str("[\\d]{2}"), // expand d to dd
                 'd' => regex.push_str(format!("[\\d]{{{}}}", token.len()).as_str()),
@@ -197,7 +209,12 @@ pub fn time_format_to_regex(
                 // time zone offset
                 'Z' => {
                     if token.len() == 1 || token.len() == 2 {
+                        // Z, -0800 or +05:30
                         regex.push_str("(?:Z|[+-]\\d\\d:?\\d\\d)");
+                    } else if capture_tz {
+                        // time zone id, e.g. Europe/Paris
+                        tz_captured = true;
+                        regex.push_str("(?P<tz>[\\w]+(?:/[\\w]+)?)");
                     } else {
                         regex.push_str("[\\w]+(?:/[\\w]+)?");
                     }
@@ -228,6 +245,22 @@ pub fn time_format_to_regex(
 
 pub fn apply_date_filter(value: &Value, filter: &DateFilter) -> Result<Value, GrokRuntimeError> {
     match value {
+        // epoch milliseconds(x) or seconds(X)
+        Value::Bytes(bytes) if filter.original_format == "x" || filter.original_format == "X" => {
+            let epoch = String::from_utf8_lossy(bytes)
+                .parse::<i64>()
+                .map_err(|error| {
+                    warn!(message = "Error parsing epoch timestamp", value = %value, % error);
+                    GrokRuntimeError::FailedToApplyFilter(filter.to_string(), value.to_string())
+                })?;
+            if filter.original_format == "x" {
+                Ok(epoch.into())
+            } else {
+                epoch.checked_mul(1000).map(Into::into).ok_or_else(|| {
+                    GrokRuntimeError::FailedToApplyFilter(filter.to_string(), value.to_string())
+                })
+            }
+        }
         Value::Bytes(bytes) => {
             let mut value = String::from_utf8_lossy(bytes).into_owned();
             // Ideally this Z should be quoted in the pattern, but DataDog supports this as a special case:
@@ -1,11 +1,18 @@
 use std::collections::BTreeMap;
 
+use lookup::{FieldBuf, LookupBuf, SegmentBuf};
+use rayon::prelude::*;
 use tracing::warn;
 use value::Value;
+use vector_common::btreemap;
+#[cfg(feature = "internal-events")]
+use vector_common::internal_event::emit;
 use vrl_compiler::Target;
 
+#[cfg(feature = "internal-events")]
+use crate::internal_events::{GrokNoRuleMatched, GrokRuleMatched};
 use crate::{
-    grok_filter::apply_filter,
+    grok_filter::apply_filter_to_field,
     parse_grok_rules::{GrokField, GrokRule},
 };
 
@@ -17,28 +24,177 @@ pub enum Error {
     NoMatch,
 }
 
+/// An error of running a single rule with `GrokRule::run`.
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum RunError {
+    #[error("failed to apply filter '{}' to field '{}': {}", .filter, .field, .error)]
+    FailedToApplyFilter {
+        field: String,
+        filter: String,
+        error: String,
+    },
+}
+
+/// The field, which lists filters failed to apply, if errors are collected.
+pub const GROK_ERRORS_FIELD: &str = "_grok_errors";
+
 /// Parses a given source field value by applying the list of grok rules until the first match found.
 pub fn parse_grok(
     source_field: &str,
     grok_rules: &[GrokRule],
     remove_empty: bool,
 ) -> Result<Value, Error> {
-    for rule in grok_rules {
-        match apply_grok_rule(source_field, rule, remove_empty) {
+    parse_grok_rules_until_match(source_field, grok_rules, remove_empty, false)
+}
+
+/// Same as `parse_grok`, but filters that fail to apply are listed in the `_grok_errors` array
+/// of the result, as objects with the `field`, `filter` and `error` keys.
+pub fn parse_grok_collecting_errors(
+    source_field: &str,
+    grok_rules: &[GrokRule],
+    remove_empty: bool,
+) -> Result<Value, Error> {
+    parse_grok_rules_until_match(source_field, grok_rules, remove_empty, true)
+}
+
+/// The number of rules from which a `GrokRuleSet` evaluates them in parallel: with fewer rules
+/// the cost of spreading the work over threads outweighs the gain, see the `parse_grok` bench.
+const PARALLEL_RULES_THRESHOLD: usize = 32;
+
+/// A set of grok rules, which are evaluated in parallel if there are many of them.
+/// As with `parse_grok`, the first rule that matches, in the order of the rules, wins.
+#[derive(Clone, Debug)]
+pub struct GrokRuleSet {
+    rules: Vec<GrokRule>,
+}
+
+impl GrokRuleSet {
+    pub fn new(rules: Vec<GrokRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn rules(&self) -> &[GrokRule] {
+        &self.rules
+    }
+
+    /// Returns the index of the first rule, that matches a given string.
+    pub fn find_match(&self, source_field: &str) -> Option<usize> {
+        if self.rules.len() < PARALLEL_RULES_THRESHOLD {
+            self.rules
+                .iter()
+                .position(|rule| rule.pattern.is_match(source_field))
+        } else {
+            self.rules
+                .par_iter()
+                .position_first(|rule| rule.pattern.is_match(source_field))
+        }
+    }
+
+    /// Parses a given source field value with the first matching rule.
+    pub fn parse(&self, source_field: &str, remove_empty: bool) -> Result<Value, Error> {
+        match self.find_match(source_field) {
+            Some(index) => {
+                #[cfg(feature = "internal-events")]
+                emit(GrokRuleMatched { rule_index: index });
+                apply_grok_rule(source_field, &self.rules[index], remove_empty, false)
+            }
+            None => {
+                #[cfg(feature = "internal-events")]
+                emit(GrokNoRuleMatched);
+                Err(Error::NoMatch)
+            }
+        }
+    }
+}
+
+impl GrokRule {
+    /// Runs this rule against a given string: extracts the captured fields and applies their filters.
+    /// Returns the extracted fields, `None` if the rule does not match, or the first filter that
+    /// failed to apply.
+    pub fn run(&self, input: &str) -> Result<Option<BTreeMap<String, Value>>, RunError> {
+        match run_grok_rule(input, self, false) {
+            Some((parsed, errors)) => match errors.into_iter().next() {
+                Some(error) => Err(error),
+                None => Ok(parsed.into_object()),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "internal-events"), allow(unused_variables))]
+fn parse_grok_rules_until_match(
+    source_field: &str,
+    grok_rules: &[GrokRule],
+    remove_empty: bool,
+    collect_errors: bool,
+) -> Result<Value, Error> {
+    for (rule_index, rule) in grok_rules.iter().enumerate() {
+        match apply_grok_rule(source_field, rule, remove_empty, collect_errors) {
             Err(Error::NoMatch) => continue,
-            other => return other,
+            other => {
+                #[cfg(feature = "internal-events")]
+                emit(GrokRuleMatched { rule_index });
+                return other;
+            }
         }
     }
+    #[cfg(feature = "internal-events")]
+    emit(GrokNoRuleMatched);
     Err(Error::NoMatch)
 }
 
 /// Tries to parse a given string with a given grok rule.
 /// Returns a result value or an error otherwise.
 /// Possible errors:
-/// - FailedToApplyFilter - matches the rule, but there was a runtime error while applying on of the filters
 /// - NoMatch - this rule does not match a given string
-fn apply_grok_rule(source: &str, grok_rule: &GrokRule, remove_empty: bool) -> Result<Value, Error> {
+///
+/// A filter that fails to apply is not an error: its field is left out and, if errors are
+/// collected, the failure is listed in the `_grok_errors` field.
+fn apply_grok_rule(
+    source: &str,
+    grok_rule: &GrokRule,
+    remove_empty: bool,
+    collect_errors: bool,
+) -> Result<Value, Error> {
+    let (mut parsed, errors) =
+        run_grok_rule(source, grok_rule, remove_empty).ok_or(Error::NoMatch)?;
+
+    if collect_errors && !errors.is_empty() {
+        let errors = errors
+            .into_iter()
+            .map(
+                |RunError::FailedToApplyFilter {
+                     field,
+                     filter,
+                     error,
+                 }| {
+                    Value::from(btreemap! {
+                        "field" => field,
+                        "filter" => filter,
+                        "error" => error,
+                    })
+                },
+            )
+            .collect();
+        parsed
+            .as_object_mut()
+            .expect("parsed value is not an object")
+            .insert(GROK_ERRORS_FIELD.to_string(), Value::Array(errors));
+    }
+
+    Ok(parsed)
+}
+
+/// Parses a given string with a given grok rule, returns the parsed value along with the filters
+/// that failed to apply, or `None` if the rule does not match.
+fn run_grok_rule(
+    source: &str,
+    grok_rule: &GrokRule,
+    remove_empty: bool,
+) -> Option<(Value, Vec<RunError>)> {
     let mut parsed = Value::Object(BTreeMap::new());
+    let mut errors = vec![];
 
     if let Some(ref matches) = grok_rule.pattern.match_against(source) {
         for (name, match_str) in matches.iter() {
@@ -49,18 +205,42 @@ fn apply_grok_rule(source: &str, grok_rule: &GrokRule, remove_empty: bool) -> Re
                 filters,
             }) = grok_rule.fields.get(name)
             {
-                if !match_str.is_empty() {
-                    filters.iter().for_each(|filter| {
+                let mut companions = vec![];
+                // filters are not applied to empty matches(e.g. of an alternative that did not match),
+                // unless they provide a default value
+                filters
+                    .iter()
+                    .filter(|filter| !match_str.is_empty() || filter.applies_to_empty())
+                    .for_each(|filter| {
                         if let Some(ref v) = value {
-                            match apply_filter(v, filter) {
-                                Ok(v) => value = Some(v),
+                            match apply_filter_to_field(v, filter) {
+                                // the filter can also remove the field
+                                Ok(output) => {
+                                    value = output.value;
+                                    companions.extend(output.companions);
+                                }
                                 Err(error) => {
                                     warn!(message = "Error applying filter", field = %field, filter = %filter, %error);
+                                    errors.push(RunError::FailedToApplyFilter {
+                                        field: field.to_string(),
+                                        filter: filter.to_string(),
+                                        error: error.to_string(),
+                                    });
                                     value = None;
                                 }
                             }
                         }
                     });
+
+                // companion fields are only stored along with the field
+                if value.is_some() {
+                    for (suffix, companion) in companions {
+                        if let Some(companion_field) = companion_field(field, suffix) {
+                            parsed.target_insert(&companion_field, companion).unwrap_or_else(
+                                |error| warn!(message = "Error updating field value", field = %companion_field, %error)
+                            );
+                        }
+                    }
                 }
 
                 if let Some(value) = value {
@@ -108,9 +288,25 @@ fn apply_grok_rule(source: &str, grok_rule: &GrokRule, remove_empty: bool) -> Re
             }
         }
 
-        Ok(parsed)
+        Some((parsed, errors))
     } else {
-        Err(Error::NoMatch)
+        None
+    }
+}
+
+/// Returns a sibling path `<field>_<suffix>` for a given field path, e.g. `network.client.ip_version`.
+fn companion_field(field: &LookupBuf, suffix: &str) -> Option<LookupBuf> {
+    let mut companion = field.clone();
+    match companion.pop_back() {
+        Some(SegmentBuf::Field(name)) => {
+            companion.push_back(SegmentBuf::field(FieldBuf::from(format!(
+                "{}_{}",
+                name.as_str(),
+                suffix
+            ))));
+            Some(companion)
+        }
+        _ => None,
     }
 }
 
@@ -119,10 +315,13 @@ mod tests {
     use ordered_float::NotNan;
     use tracing_test::traced_test;
     use value::Value;
-    use vector_common::btreemap;
 
     use super::*;
-    use crate::parse_grok_rules::parse_grok_rules;
+    use crate::grok_filter::GrokFilter;
+    use crate::parse_grok_rules::{
+        parse_grok_rules, parse_grok_rules_with_options, Error as GrokStaticError,
+        GrokCompileOptions, MatchMode,
+    };
 
     #[test]
     fn parses_simple_grok() {
@@ -237,6 +436,44 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn applies_numeric_filters_to_numbers() {
+        test_grok_pattern(vec![
+            ("%{integer:field:integer}", "12", Ok(Value::from(12))),
+            ("%{number:field:integer}", "12.0", Ok(Value::from(12))),
+            ("%{number:field:integerExt}", "-1.5", Ok(Value::from(-1))),
+            ("%{integer:field:number}", "12", Ok(Value::from(12))),
+            ("%{number:field:numberExt}", "1.5", Ok(Value::from(1.5_f64))),
+        ]);
+        // a fraction is not an integer
+        test_full_grok(vec![(
+            "%{number:field:integer}",
+            "1.5",
+            Ok(Value::Object(BTreeMap::new())),
+        )]);
+    }
+
+    #[test]
+    fn numeric_filter_chains_report_no_errors() {
+        // before numeric input was accepted, each of these chains failed to apply its explicit
+        // filter to the number of the matcher, leaving the field out and reporting an error
+        for (rule, input, expected) in [
+            ("%{number:field:integerExt}", "-1.5", Value::from(-1)),
+            ("%{number:field:integer}", "12.0", Value::from(12)),
+            ("%{integer:field:number}", "12", Value::from(12)),
+            ("%{integerExt:field:numberExt}", "12", Value::from(12)),
+        ] {
+            let rules = parse_grok_rules(&[rule.to_string()], BTreeMap::new())
+                .expect("couldn't parse rules");
+            assert_eq!(
+                parse_grok_collecting_errors(input, &rules, false),
+                Ok(Value::from(btreemap! { "field" => expected })),
+                "{}",
+                rule
+            );
+        }
+    }
+
     fn test_grok_pattern(tests: Vec<(&str, &str, Result<Value, Error>)>) {
         for (filter, k, v) in tests {
             let rules = parse_grok_rules(&[filter.to_string()], BTreeMap::new())
@@ -266,13 +503,183 @@ mod tests {
         }
     }
 
+    #[test]
+    fn matches_substrings_in_unanchored_mode() {
+        let pattern = [r#""%{word:method} %{notSpace:path} HTTP"#.to_string()];
+        let input = r#"127.0.0.1 - - "GET /index.html HTTP/1.1" 200"#;
+
+        let anchored = parse_grok_rules(&pattern, BTreeMap::new()).expect("couldn't parse rules");
+        assert_eq!(parse_grok(input, &anchored, false), Err(Error::NoMatch));
+
+        let unanchored = parse_grok_rules_with_options(
+            &pattern,
+            BTreeMap::new(),
+            GrokCompileOptions {
+                match_mode: MatchMode::Anywhere,
+                ..Default::default()
+            },
+        )
+        .expect("couldn't parse rules");
+        assert_eq!(
+            parse_grok(input, &unanchored, false),
+            Ok(Value::from(btreemap! {
+                "method" => "GET",
+                "path" => "/index.html",
+            }))
+        );
+    }
+
+    #[test]
+    fn runs_single_rule() {
+        let rules = parse_grok_rules(
+            &[
+                r#"%{date("dd/MMM/yyyy"):date} %{integer:http.status}"#.to_string(),
+                "%{notSpace:status:integer}".to_string(),
+            ],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+
+        assert_eq!(
+            rules[0].run("06/Mar/2013 200"),
+            Ok(Some(btreemap! {
+                "date" => 1362528000000_i64,
+                "http" => btreemap! {
+                    "status" => 200,
+                },
+            }))
+        );
+        assert_eq!(rules[0].run("06/Mar/2013 OK"), Ok(None));
+
+        // a filter error is distinguishable from no match
+        assert!(matches!(
+            rules[1].run("OK"),
+            Err(RunError::FailedToApplyFilter { field, filter, .. })
+                if field == "status" && filter == "Integer"
+        ));
+    }
+
+    #[cfg(feature = "internal-events")]
+    mod internal_events {
+        use std::{cell::RefCell, collections::HashMap, sync::Once};
+
+        use metrics::{GaugeValue, Key, Recorder, Unit};
+
+        use super::*;
+
+        thread_local!(static COUNTERS: RefCell<HashMap<String, u64>> = RefCell::new(HashMap::new()));
+
+        /// Sums up counter increments per metric name and labels, e.g. `grok_matched_events_total{rule_index=0}`,
+        /// separately for each thread, so that tests running in parallel don't see each other's metrics.
+        struct CountingRecorder;
+
+        impl CountingRecorder {
+            fn install() {
+                static RECORDER: CountingRecorder = CountingRecorder;
+                static INSTALL: Once = Once::new();
+                INSTALL.call_once(|| {
+                    metrics::set_recorder(&RECORDER)
+                        .expect("a metrics recorder is already installed")
+                });
+            }
+
+            fn counter(id: &str) -> u64 {
+                COUNTERS.with(|counters| counters.borrow().get(id).copied().unwrap_or_default())
+            }
+        }
+
+        impl Recorder for CountingRecorder {
+            fn register_counter(
+                &self,
+                _key: &Key,
+                _unit: Option<Unit>,
+                _description: Option<&'static str>,
+            ) {
+            }
+
+            fn register_gauge(
+                &self,
+                _key: &Key,
+                _unit: Option<Unit>,
+                _description: Option<&'static str>,
+            ) {
+            }
+
+            fn register_histogram(
+                &self,
+                _key: &Key,
+                _unit: Option<Unit>,
+                _description: Option<&'static str>,
+            ) {
+            }
+
+            fn increment_counter(&self, key: &Key, value: u64) {
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>();
+                let id = if labels.is_empty() {
+                    key.name().to_string()
+                } else {
+                    format!("{}{{{}}}", key.name(), labels.join(","))
+                };
+                COUNTERS.with(|counters| *counters.borrow_mut().entry(id).or_default() += value);
+            }
+
+            fn update_gauge(&self, _key: &Key, _value: GaugeValue) {}
+
+            fn record_histogram(&self, _key: &Key, _value: f64) {}
+        }
+
+        #[test]
+        fn emits_rule_match_events() {
+            use vector_common::event_test_util::{clear_recorded_events, contains_name};
+
+            CountingRecorder::install();
+            let rules = parse_grok_rules(
+                &["%{integer:status}".to_string(), "%{word:level}".to_string()],
+                BTreeMap::new(),
+            )
+            .expect("couldn't parse rules");
+
+            clear_recorded_events();
+            assert!(parse_grok("info", &rules, false).is_ok());
+            assert!(contains_name("GrokRuleMatched"));
+            assert!(!contains_name("GrokNoRuleMatched"));
+
+            clear_recorded_events();
+            assert_eq!(parse_grok("a b", &rules, false), Err(Error::NoMatch));
+            assert!(!contains_name("GrokRuleMatched"));
+            assert!(contains_name("GrokNoRuleMatched"));
+
+            clear_recorded_events();
+            let rule_set = GrokRuleSet::new(rules.clone());
+            assert!(rule_set.parse("200", false).is_ok());
+            assert!(contains_name("GrokRuleMatched"));
+
+            for input in ["200", "warn", "error", "a b"] {
+                let _ = parse_grok(input, &rules, false);
+                let _ = rule_set.parse(input, false);
+            }
+            assert_eq!(
+                CountingRecorder::counter("grok_matched_events_total{rule_index=0}"),
+                3
+            );
+            assert_eq!(
+                CountingRecorder::counter("grok_matched_events_total{rule_index=1}"),
+                5
+            );
+            assert_eq!(CountingRecorder::counter("grok_unmatched_events_total"), 3);
+        }
+    }
+
     #[test]
     fn fails_on_unknown_pattern_definition() {
         assert_eq!(
             parse_grok_rules(&["%{unknown}".to_string()], BTreeMap::new())
                 .unwrap_err()
                 .to_string(),
-            r#"failed to parse grok expression '\A%{unknown}\z': The given pattern definition name "unknown" could not be found in the definition map"#
+            r#"failed to parse grok expression '\A%{unknown}\z' at offset 0: The given pattern definition name "unknown" could not be found in the definition map"#
         );
     }
 
@@ -383,6 +790,90 @@ mod tests {
         )]);
     }
 
+    #[test]
+    fn collects_filter_errors() {
+        let rules = parse_grok_rules(
+            &["%{notSpace:field1:integer} %{notSpace:field2:integer}".to_string()],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+
+        assert_eq!(
+            parse_grok_collecting_errors("abc 1", &rules, false).unwrap(),
+            Value::from(btreemap! {
+                "field2" => 1,
+                "_grok_errors" => Value::Array(vec![Value::from(btreemap! {
+                    "field" => "field1",
+                    "filter" => "Integer",
+                    "error" => r#"failed to apply filter 'Integer' to '"abc"'"#,
+                })]),
+            })
+        );
+        // errors are not collected by default
+        assert_eq!(
+            parse_grok("abc 1", &rules, false).unwrap(),
+            Value::from(btreemap! {
+                "field2" => 1,
+            })
+        );
+    }
+
+    #[test]
+    fn rule_set_preserves_rule_order() {
+        let rules = parse_grok_rules(
+            &[
+                "%{integer:first}".to_string(),
+                "%{notSpace:second}".to_string(),
+                "%{data:third}".to_string(),
+            ],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+        let rule_set = GrokRuleSet::new(rules.clone());
+
+        // all rules match, the first one wins
+        assert_eq!(rule_set.find_match("1"), Some(0));
+        assert_eq!(
+            rule_set.parse("1", false),
+            Ok(Value::from(btreemap! { "first" => 1 }))
+        );
+        // only the last two match
+        assert_eq!(rule_set.find_match("abc"), Some(1));
+        assert_eq!(
+            rule_set.parse("abc", false),
+            parse_grok("abc", &rules, false)
+        );
+        assert_eq!(rule_set.find_match("a b"), Some(2));
+        assert_eq!(
+            rule_set.parse("a b", false),
+            parse_grok("a b", &rules, false)
+        );
+        assert_eq!(
+            rule_set.parse("a\nb", false),
+            parse_grok("a\nb", &rules, false)
+        );
+    }
+
+    #[test]
+    fn large_rule_set_preserves_rule_order() {
+        // enough rules to be evaluated in parallel, the last two match any number
+        let mut patterns = (0..PARALLEL_RULES_THRESHOLD)
+            .map(|i| format!("rule{} %{{integer:status}}", i))
+            .collect::<Vec<_>>();
+        patterns.push("%{integer:first}".to_string());
+        patterns.push("%{number:second}".to_string());
+        let rules = parse_grok_rules(&patterns, BTreeMap::new()).expect("couldn't parse rules");
+        let rule_set = GrokRuleSet::new(rules);
+
+        assert_eq!(rule_set.find_match("rule3 200"), Some(3));
+        assert_eq!(rule_set.find_match("1"), Some(PARALLEL_RULES_THRESHOLD));
+        assert_eq!(
+            rule_set.find_match("1.5"),
+            Some(PARALLEL_RULES_THRESHOLD + 1)
+        );
+        assert_eq!(rule_set.find_match("abc"), None);
+    }
+
     #[test]
     fn fails_on_no_match() {
         let rules = parse_grok_rules(
@@ -555,6 +1046,46 @@ mod tests {
                 "171113 14:14:20",
                 Ok(Value::Integer(1510582460000)),
             ),
+            (
+                r#"%{date("yyyy-MM-dd'T'HH:mm:ss.SSSSSSZ"):field}"#,
+                "2016-11-29T16:21:36.431123+0000",
+                Ok(Value::Integer(1480436496431)),
+            ),
+            (
+                r#"%{date("yyyy-MM-dd HH:mm:ss,SSS"):field}"#,
+                "2016-11-29 16:21:36,431",
+                Ok(Value::Integer(1480436496431)),
+            ),
+            (
+                r#"%{date("yyyy-MM-dd'T'HH:mm:ss.SSSZ"):field}"#,
+                "2016-11-29T16:21:36.431+05:30",
+                Ok(Value::Integer(1480416696431)),
+            ),
+            (
+                r#"%{date("yyyy-MM-dd'T'HH:mm:ss.SSSZ"):field}"#,
+                "2016-11-29T16:21:36.431-0800",
+                Ok(Value::Integer(1480465296431)),
+            ),
+            (
+                r#"%{date("yyyy-MM-dd'T'HH:mm:ss.SSSZZ"):field}"#,
+                "2016-11-29T16:21:36.431Z",
+                Ok(Value::Integer(1480436496431)),
+            ),
+            (
+                r#"%{date("yyyy-MM-dd HH:mm:ss.SSS ZZZ"):field}"#,
+                "2016-11-29 16:21:36.431 Europe/Paris",
+                Ok(Value::Integer(1480432896431)),
+            ),
+            (
+                r#"%{date("x"):field}"#,
+                "1480436496431",
+                Ok(Value::Integer(1480436496431)),
+            ),
+            (
+                r#"%{date("X"):field}"#,
+                "1480436496",
+                Ok(Value::Integer(1480436496000)),
+            ),
         ]);
 
         // check error handling
@@ -576,6 +1107,18 @@ mod tests {
             .to_string(),
             r#"invalid arguments for the function 'date'"#
         );
+        // epoch timestamps can't be combined with other fields
+        for format in ["x.SSS", "X.SSS", "xx", "yyyy-MM-dd x"] {
+            assert_eq!(
+                parse_grok_rules(
+                    &[format!(r#"%{{date("{}"):field}}"#, format)],
+                    BTreeMap::new()
+                )
+                .unwrap_err()
+                .to_string(),
+                r#"invalid arguments for the function 'date'"#
+            );
+        }
     }
 
     #[test]
@@ -864,6 +1407,527 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn supports_null_and_default_filters() {
+        test_grok_pattern(vec![
+            (r#"%{notSpace:field:nullIf("-")}"#, "-", Ok(Value::Null)),
+            (
+                r#"%{regex("[a-z-]*"):field:defaultTo("unknown")}"#,
+                "",
+                Ok(Value::from("unknown")),
+            ),
+            (
+                r#"%{regex("[a-z-]*"):field:defaultTo("unknown")}"#,
+                "abc",
+                Ok(Value::from("abc")),
+            ),
+            (
+                r#"%{regex("[a-z-]*"):field:emptyStringIfNull}"#,
+                "abc",
+                Ok(Value::from("abc")),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn applies_default_to_nulled_value() {
+        // only one explicit filter can follow the destination, so defaultTo is appended to the
+        // filters of a parsed nullIf rule, which then runs as any other rule
+        let mut rules = parse_grok_rules(
+            &[r#"%{notSpace:field:nullIf("-")}"#.to_string()],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+        for field in rules[0].fields.values_mut() {
+            field.filters.push(GrokFilter::DefaultTo("unknown".into()));
+        }
+
+        assert_eq!(
+            parse_grok("-", &rules, false),
+            Ok(Value::from(btreemap! { "field" => "unknown" }))
+        );
+        assert_eq!(
+            parse_grok("abc", &rules, false),
+            Ok(Value::from(btreemap! { "field" => "abc" }))
+        );
+    }
+
+    #[test]
+    fn rejects_chained_filters() {
+        // only one explicit filter can follow the destination
+        assert!(matches!(
+            parse_grok_rules(
+                &[r#"%{notSpace:field:nullIf("-"):defaultTo("unknown")}"#.to_string()],
+                BTreeMap::new(),
+            ),
+            Err(GrokStaticError::InvalidGrokExpression(..))
+        ));
+    }
+
+    #[test]
+    fn supports_number_with_grouping_filter() {
+        test_grok_pattern(vec![
+            (
+                "%{data:field:numberWithGrouping}",
+                "1,234,567",
+                Ok(Value::from(1234567)),
+            ),
+            (
+                "%{data:field:numberWithGrouping}",
+                "1,234.56",
+                Ok(Value::from(1234.56)),
+            ),
+            (
+                r#"%{data:field:numberWithGrouping(".", ",")}"#,
+                "1.234,56",
+                Ok(Value::from(1234.56)),
+            ),
+            (
+                "%{data:field:numberWithGrouping}",
+                "1234",
+                Ok(Value::from(1234)),
+            ),
+        ]);
+        test_full_grok(vec![
+            (
+                "%{data:field:numberWithGrouping}",
+                "1,2a4",
+                Ok(Value::from(BTreeMap::new())),
+            ),
+            (
+                r#"%{data:field:numberWithGrouping(".", ",")}"#,
+                "1.234,56,7",
+                Ok(Value::from(BTreeMap::new())),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn splits_adjacent_data_captures() {
+        test_full_grok(vec![
+            // `data` is lazy, so the first capture stops at the first separator
+            (
+                "%{data:first} - %{data:second}",
+                "a - b - c",
+                Ok(Value::from(btreemap! {
+                    "first" => "a",
+                    "second" => "b - c",
+                })),
+            ),
+            // while `greedyData` stops at the last one
+            (
+                "%{greedyData:first} - %{data:second}",
+                "a - b - c",
+                Ok(Value::from(btreemap! {
+                    "first" => "a - b",
+                    "second" => "c",
+                })),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_escaped_quoted_string_matcher() {
+        test_grok_pattern(vec![
+            (
+                "%{escapedQuotedString:field}",
+                r#""say \"hi\" to \\ them""#,
+                Ok(Value::from(r#"say "hi" to \ them"#)),
+            ),
+            (
+                "%{escapedQuotedString:field}",
+                r#"'it\'s "quoted"'"#,
+                Ok(Value::from(r#"it's "quoted""#)),
+            ),
+        ]);
+        test_full_grok(vec![
+            (
+                r#"msg=%{escapedQuotedString:msg}, user=%{word:user}"#,
+                r#"msg="a, \"b\", c", user=bob"#,
+                Ok(Value::from(btreemap! {
+                    "msg" => r#"a, "b", c"#,
+                    "user" => "bob",
+                })),
+            ),
+            (
+                "%{escapedQuotedString:field}",
+                r#""unterminated \""#,
+                Err(Error::NoMatch),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_null_if_matches_filter() {
+        let rule = r#"%{notSpace:user:nullIfMatches("N/A|-|null")} %{notSpace:status}"#;
+        test_full_grok(vec![
+            (
+                rule,
+                "N/A 200",
+                Ok(Value::from(btreemap! {
+                    "status" => "200",
+                })),
+            ),
+            (
+                rule,
+                "- 200",
+                Ok(Value::from(btreemap! {
+                    "status" => "200",
+                })),
+            ),
+            // must match the whole value
+            (
+                rule,
+                "N/A-bob 200",
+                Ok(Value::from(btreemap! {
+                    "user" => "N/A-bob",
+                    "status" => "200",
+                })),
+            ),
+        ]);
+        assert_eq!(
+            parse_grok_rules(
+                &[r#"%{notSpace:user:nullIfMatches("(")}"#.to_string()],
+                BTreeMap::new()
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid arguments for the function 'nullIfMatches'"
+        );
+    }
+
+    #[test]
+    fn supports_split_filter() {
+        test_grok_pattern(vec![
+            (
+                r#"%{notSpace:field:split("/")}"#,
+                "/a/b/c",
+                Ok(Value::Array(vec!["a".into(), "b".into(), "c".into()])),
+            ),
+            (
+                r#"%{notSpace:field:split("/", 2)}"#,
+                "/a/b/c",
+                Ok(Value::from("c")),
+            ),
+            (
+                r#"%{notSpace:field:split(".", 0)}"#,
+                "com.example.app",
+                Ok(Value::from("com")),
+            ),
+        ]);
+        test_full_grok(vec![(
+            r#"%{notSpace:field:split("/", 3)}"#,
+            "/a/b/c",
+            Ok(Value::from(BTreeMap::new())),
+        )]);
+    }
+
+    #[test]
+    fn supports_byte_size_filter() {
+        test_grok_pattern(vec![
+            ("%{data:field:byteSize}", "10KB", Ok(Value::from(10000))),
+            (
+                "%{data:field:byteSize}",
+                "1.5 MiB",
+                Ok(Value::from(1572864)),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_xml_filter() {
+        test_full_grok(vec![(
+            "%{word:level} %{data:payload:xml}",
+            r#"error <error code="500"><message>timeout</message></error>"#,
+            Ok(Value::from(btreemap! {
+                "level" => "error",
+                "payload" => btreemap! {
+                    "error" => btreemap! {
+                        "@code" => "500",
+                        "message" => "timeout",
+                    },
+                },
+            })),
+        )]);
+
+        test_grok_pattern(vec![
+            (
+                "%{data:field:xml(true)}",
+                r#"<error code="500">timeout</error>"#,
+                Ok(Value::from(btreemap! {
+                    "error" => btreemap! {
+                        "@attributes" => btreemap! {
+                            "code" => "500",
+                        },
+                        "#text" => "timeout",
+                    },
+                })),
+            ),
+            (
+                "%{data:field:xml}",
+                "<error>timeout",
+                Ok(Value::from("<error>timeout")),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_semver_filter() {
+        test_grok_pattern(vec![
+            (
+                "%{data:field:semver}",
+                "1.2.3-rc.1+build.5",
+                Ok(Value::from(btreemap! {
+                    "major" => 1,
+                    "minor" => 2,
+                    "patch" => 3,
+                    "prerelease" => "rc.1",
+                    "build" => "build.5",
+                })),
+            ),
+            (
+                "%{data:field:semver}",
+                "1.2.3",
+                Ok(Value::from(btreemap! {
+                    "major" => 1,
+                    "minor" => 2,
+                    "patch" => 3,
+                })),
+            ),
+            // not a valid semantic version - kept as is
+            ("%{data:field:semver}", "1.2", Ok(Value::from("1.2"))),
+        ]);
+    }
+
+    #[test]
+    fn supports_semver_matcher() {
+        test_full_grok(vec![
+            (
+                "release %{semver:version} deployed",
+                "release 2.0.0-beta deployed",
+                Ok(Value::from(btreemap! {
+                    "version" => btreemap! {
+                        "major" => 2,
+                        "minor" => 0,
+                        "patch" => 0,
+                        "prerelease" => "beta",
+                    },
+                })),
+            ),
+            (
+                "release %{semver:version} deployed",
+                "release 1.2 deployed",
+                Err(Error::NoMatch),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_hash_filter() {
+        test_grok_pattern(vec![
+            (
+                "%{notSpace:field:hash}",
+                "abc",
+                Ok(Value::from(
+                    "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                )),
+            ),
+            (
+                r#"%{notSpace:field:hash("sha3")}"#,
+                "abc",
+                Ok(Value::from(
+                    "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+                )),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_geoip_filter() {
+        let database = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../../tests/data/GeoIP2-City-Test.mmdb"
+        );
+        test_grok_pattern(vec![
+            (
+                &format!(r#"%{{ip:field:geoip("{}", "country_code")}}"#, database),
+                "2.125.160.216",
+                Ok(Value::from(btreemap! { "country_code" => "GB" })),
+            ),
+            (
+                &format!(r#"%{{ip:field:geoip("{}", "country_code")}}"#, database),
+                "10.0.0.1",
+                Ok(Value::from(BTreeMap::new())),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_decode_base64_filter() {
+        test_grok_pattern(vec![
+            (
+                "%{notSpace:field:decodeBase64}",
+                "aGk/Pg==",
+                Ok(Value::from("hi?>")),
+            ),
+            (
+                "%{notSpace:field:decodeBase64(true)}",
+                "Pz8-Pw==",
+                Ok(Value::from("??>?")),
+            ),
+            // not valid in the standard alphabet
+            (
+                "%{notSpace:field:decodeBase64}",
+                "Pz8-Pw==",
+                Ok(Value::from("Pz8-Pw==")),
+            ),
+            // invalid padding
+            (
+                "%{notSpace:field:decodeBase64}",
+                "aGk/Pg=x",
+                Ok(Value::from("aGk/Pg=x")),
+            ),
+        ]);
+        test_full_grok(vec![(
+            "%{notSpace:field:decodeBase64(false, true)}",
+            "aGk/Pg=x",
+            Ok(Value::from(BTreeMap::new())),
+        )]);
+    }
+
+    #[test]
+    fn supports_duration_filter() {
+        test_grok_pattern(vec![
+            (
+                "%{notSpace:field:duration}",
+                "1.5ms",
+                Ok(Value::from(1500000)),
+            ),
+            (
+                r#"%{notSpace:field:duration("ms")}"#,
+                "2s",
+                Ok(Value::from(2000)),
+            ),
+        ]);
+        assert_eq!(
+            parse_grok_rules(
+                &[r#"%{notSpace:field:duration("weeks")}"#.to_string()],
+                BTreeMap::new()
+            )
+            .unwrap_err()
+            .to_string(),
+            "invalid arguments for the function 'duration'"
+        );
+    }
+
+    #[test]
+    fn supports_ip_filter() {
+        test_full_grok(vec![
+            (
+                "%{notSpace:client.ip:ip(true)}",
+                "10.0.0.1",
+                Ok(Value::from(btreemap! {
+                    "client" => btreemap! {
+                        "ip" => "10.0.0.1",
+                        "ip_version" => 4,
+                    },
+                })),
+            ),
+            (
+                "%{notSpace:client.ip:ip(true)}",
+                "2001:DB8:0:0:0:0:0:1",
+                Ok(Value::from(btreemap! {
+                    "client" => btreemap! {
+                        "ip" => "2001:db8::1",
+                        "ip_version" => 6,
+                    },
+                })),
+            ),
+            (
+                "%{notSpace:client.ip:ip}",
+                "2001:DB8:0:0:0:0:0:1",
+                Ok(Value::from(btreemap! {
+                    "client" => btreemap! {
+                        "ip" => "2001:db8::1",
+                    },
+                })),
+            ),
+            // malformed addresses are left untouched
+            (
+                "%{notSpace:client.ip:ip(true)}",
+                "999.0.0.1",
+                Ok(Value::from(btreemap! {
+                    "client" => btreemap! {
+                        "ip" => "999.0.0.1",
+                    },
+                })),
+            ),
+            // or fail the filter in the strict mode
+            (
+                "%{notSpace:client.ip:ip(true, true)}",
+                "999.0.0.1",
+                Ok(Value::from(BTreeMap::new())),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn supports_trim_filters() {
+        test_grok_pattern(vec![
+            (
+                "%{data:field:trim}",
+                " \tabc \t",
+                Ok(Value::Bytes("abc".into())),
+            ),
+            (
+                "%{data:field:trimLeft}",
+                "  abc  ",
+                Ok(Value::Bytes("abc  ".into())),
+            ),
+            (
+                "%{data:field:trimRight}",
+                "  abc  ",
+                Ok(Value::Bytes("  abc".into())),
+            ),
+            (
+                r#"%{data:field:trim("\"'")}"#,
+                r#""'abc'""#,
+                Ok(Value::Bytes("abc".into())),
+            ),
+            (
+                r#"%{data:field:trimLeft("\"")}"#,
+                r#""abc""#,
+                Ok(Value::Bytes(r#"abc""#.into())),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn parses_csv() {
+        test_grok_pattern(vec![
+            (
+                "%{data:field:csv}",
+                r#"a,"b,c",d"#,
+                Ok(Value::Array(vec!["a".into(), "b,c".into(), "d".into()])),
+            ),
+            (
+                r#"%{data:field:csv(";", "first;second")}"#,
+                "1;2",
+                Ok(Value::from(btreemap! {
+                    "first" => "1",
+                    "second" => "2",
+                })),
+            ),
+        ]);
+
+        assert_eq!(
+            parse_grok_rules(&[r#"%{data:field:csv(";;")}"#.to_string()], BTreeMap::new())
+                .unwrap_err()
+                .to_string(),
+            r#"invalid arguments for the function 'csv'"#
+        );
+    }
+
     #[test]
     fn alias_and_main_rule_extract_same_fields_to_array() {
         let rules = parse_grok_rules(
@@ -992,4 +2056,29 @@ mod tests {
             ),
         ]);
     }
+
+    #[test]
+    fn supports_dotall_compile_option() {
+        let rules = parse_grok_rules_with_options(
+            &["%{word:level} %{data:message}".to_string()],
+            BTreeMap::new(),
+            GrokCompileOptions {
+                dot_matches_new_line: true,
+                ..Default::default()
+            },
+        )
+        .expect("couldn't parse rules");
+
+        assert_eq!(
+            parse_grok(
+                "ERROR java.lang.NullPointerException\n\tat Main.main(Main.java:5)",
+                &rules,
+                false
+            ),
+            Ok(Value::from(btreemap! {
+                "level" => "ERROR",
+                "message" => "java.lang.NullPointerException\n\tat Main.main(Main.java:5)",
+            }))
+        );
+    }
 }
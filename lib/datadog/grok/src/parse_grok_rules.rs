@@ -1,6 +1,7 @@
 use std::{
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
+    ops::Range,
 };
 
 use lookup::LookupBuf;
@@ -11,7 +12,7 @@ use value::Value;
 use crate::grok::Grok;
 use crate::{
     ast::{self, Destination, GrokPattern},
-    grok_filter::GrokFilter,
+    grok_filter::{validate_filter_chain, GrokFilter},
     matchers::{date, date::DateFilter},
     parse_grok_pattern::parse_grok_pattern,
 };
@@ -29,6 +30,36 @@ pub struct GrokRule {
     pub fields: HashMap<String, GrokField>,
 }
 
+impl GrokRule {
+    /// Matches this rule against a given string and returns the raw captures(grok0, grok1, ... -> matched text)
+    /// without applying any filters, or `None` if the rule does not match.
+    /// Use `fields` to find the destination and the filters of each capture.
+    pub fn captures<'a>(&'a self, input: &'a str) -> Option<HashMap<&'a str, &'a str>> {
+        self.pattern
+            .match_against(input)
+            .map(|matches| matches.into_map())
+    }
+
+    /// Returns the lookup paths this rule extracts values to, in the order they first appear in the rule.
+    /// A path the rule extracts several values to, e.g. `%{word:tag} %{word:tag}`, is returned once.
+    pub fn destinations(&self) -> Vec<&LookupBuf> {
+        let mut fields = self.fields.iter().collect::<Vec<_>>();
+        // capture names are generated sequentially(grok0, grok1, ...)
+        fields.sort_by_key(|(name, _)| {
+            name.trim_start_matches("grok")
+                .parse::<usize>()
+                .unwrap_or(usize::MAX)
+        });
+        let mut destinations: Vec<&LookupBuf> = Vec::with_capacity(fields.len());
+        for (_, field) in fields {
+            if !destinations.contains(&&field.lookup) {
+                destinations.push(&field.lookup);
+            }
+        }
+        destinations
+    }
+}
+
 /// A grok field, that should be extracted, with its lookup path and
 /// post-processing filters to apply.
 #[derive(Debug, Clone)]
@@ -48,6 +79,8 @@ pub struct GrokRuleParseContext {
     pub aliases: BTreeMap<String, String>,
     /// used to detect cycles in alias definitions
     pub alias_stack: Vec<String>,
+    /// the byte offsets of the rule's expressions along with the ranges of their regular expressions
+    pub expressions: Vec<(usize, Range<usize>)>,
 }
 
 impl GrokRuleParseContext {
@@ -74,9 +107,19 @@ impl GrokRuleParseContext {
             fields: HashMap::new(),
             aliases,
             alias_stack: vec![],
+            expressions: vec![],
         }
     }
 
+    /// Returns the byte offset of the first expression of the rule, whose regular expression
+    /// fails to compile on its own, or `0`(the whole rule) if each of them compiles.
+    fn invalid_expression_offset(&self, grok: &mut Grok) -> usize {
+        self.expressions
+            .iter()
+            .find(|(_, range)| grok.compile(&self.regex[range.clone()], true).is_err())
+            .map_or(0, |(offset, _)| *offset)
+    }
+
     /// Generates a grok-safe name for a given field(grok0, grok1 ...)
     fn generate_grok_compliant_name(&mut self) -> String {
         format!("grok{}", self.fields.len())
@@ -85,14 +128,53 @@ impl GrokRuleParseContext {
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum Error {
-    #[error("failed to parse grok expression '{}': {}", .0, .1)]
-    InvalidGrokExpression(String, String),
+    /// an invalid expression, the error message and, if known, the byte offset of the expression in the rule(or alias) definition
+    #[error("failed to parse grok expression '{}'{}: {}", .0, offset_suffix(.2), .1)]
+    InvalidGrokExpression(String, String, Option<usize>),
     #[error("invalid arguments for the function '{}'", .0)]
     InvalidFunctionArguments(String),
     #[error("unknown filter '{}'", .0)]
     UnknownFilter(String),
     #[error("Circular dependency found in the alias '{}'", .0)]
     CircularDependencyInAliasDefinition(String),
+    #[error("filter '{}' can't be applied to the output of the filter '{}'", .1, .0)]
+    IncompatibleFilters(String, String),
+}
+
+fn offset_suffix(offset: &Option<usize>) -> String {
+    offset
+        .map(|offset| format!(" at offset {}", offset))
+        .unwrap_or_default()
+}
+
+/// Controls which part of the input a grok rule has to match.
+///
+/// DataDog grok rules are anchored: `%{integer:status}` matches `200`, but not `status 200 OK`.
+/// Logstash grok patterns are not anchored and extract values from the first matching substring,
+/// which is what `Anywhere` does - leading and trailing text around the match is ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchMode {
+    /// the rule has to match the whole input string(the default, DataDog semantics)
+    FullString,
+    /// the rule may match anywhere in the input string(Logstash semantics)
+    Anywhere,
+}
+
+impl Default for MatchMode {
+    fn default() -> Self {
+        MatchMode::FullString
+    }
+}
+
+/// Options used to compile grok rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GrokCompileOptions {
+    /// whether the rules should match case-insensitively
+    pub case_insensitive: bool,
+    /// whether `.` should match line terminators too(as if the rules started with `(?s)`), e.g. for multiline stack traces
+    pub dot_matches_new_line: bool,
+    /// whether the rules should match the whole input or any part of it
+    pub match_mode: MatchMode,
 }
 
 ///
@@ -116,6 +198,15 @@ pub enum Error {
 pub fn parse_grok_rules(
     patterns: &[String],
     aliases: BTreeMap<String, String>,
+) -> Result<Vec<GrokRule>, Error> {
+    parse_grok_rules_with_options(patterns, aliases, GrokCompileOptions::default())
+}
+
+/// Parses DD grok rules(see `parse_grok_rules`) with the given compile options.
+pub fn parse_grok_rules_with_options(
+    patterns: &[String],
+    aliases: BTreeMap<String, String>,
+    options: GrokCompileOptions,
 ) -> Result<Vec<GrokRule>, Error> {
     let mut grok = Grok::with_patterns();
 
@@ -127,11 +218,38 @@ pub fn parse_grok_rules(
                 r,
                 &mut GrokRuleParseContext::new(aliases.clone()),
                 &mut grok,
+                options,
             )
         })
         .collect::<Result<Vec<GrokRule>, Error>>()
 }
 
+/// Compiles each of the given DD grok rules(see `parse_grok_rules`) and reports whether it is valid, without keeping the compiled rules around.
+/// Unlike `parse_grok_rules`, it doesn't stop at the first invalid rule: the result contains one entry per pattern, in the same order.
+/// Empty patterns are ignored by `parse_grok_rules` and are always reported as valid.
+pub fn validate_grok_rules(
+    patterns: &[String],
+    aliases: BTreeMap<String, String>,
+) -> Vec<Result<(), Error>> {
+    let mut grok = Grok::with_patterns();
+
+    patterns
+        .iter()
+        .map(|r| {
+            if r.is_empty() {
+                return Ok(());
+            }
+            parse_pattern(
+                r,
+                &mut GrokRuleParseContext::new(aliases.clone()),
+                &mut grok,
+                GrokCompileOptions::default(),
+            )
+            .map(|_| ())
+        })
+        .collect()
+}
+
 ///
 /// Parses alias definitions.
 ///
@@ -169,25 +287,46 @@ fn parse_alias(
 /// - `pattern` - the definition of the pattern
 /// - `context` - the context required to parse the current grok rule
 /// - `grok` - an instance of Grok parser
+/// - `options` - compile options
 fn parse_pattern(
     pattern: &str,
     context: &mut GrokRuleParseContext,
     grok: &mut Grok,
+    options: GrokCompileOptions,
 ) -> Result<GrokRule, Error> {
     parse_grok_rule(pattern, context)?;
     let mut pattern = String::new();
-    // \A, \z - parses from the beginning to the end of string, not line(until \n)
-    pattern.push_str(r#"\A"#);
-    pattern.push_str(&context.regex);
-    pattern.push_str(r#"\z"#);
+    if options.case_insensitive {
+        pattern.push_str("(?i)");
+    }
+    if options.dot_matches_new_line {
+        pattern.push_str("(?s)");
+    }
+    match options.match_mode {
+        MatchMode::FullString => {
+            // \A, \z - parses from the beginning to the end of string, not line(until \n)
+            pattern.push_str(r#"\A"#);
+            pattern.push_str(&context.regex);
+            pattern.push_str(r#"\z"#);
+        }
+        MatchMode::Anywhere => pattern.push_str(&context.regex),
+    }
 
     // our regex engine(onig) uses (?m) mode modifier instead of (?s) to make the dot match all characters
     pattern = pattern.replace("(?s)", "(?m)").replace("(?-s)", "(?-m)");
 
     // compile pattern
-    let pattern = grok
-        .compile(&pattern, true)
-        .map_err(|e| Error::InvalidGrokExpression(pattern, e.to_string()))?;
+    let pattern = match grok.compile(&pattern, true) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            let offset = context.invalid_expression_offset(grok);
+            return Err(Error::InvalidGrokExpression(
+                pattern,
+                e.to_string(),
+                Some(offset),
+            ));
+        }
+    };
 
     Ok(GrokRule {
         pattern,
@@ -207,9 +346,17 @@ fn parse_grok_rule(rule: &str, context: &mut GrokRuleParseContext) -> Result<(),
     for (start, end) in GROK_PATTERN_RE.find_iter(rule) {
         context.append_regex(&rule[regex_i..start]);
         regex_i = end;
-        let pattern = parse_grok_pattern(&rule[start..end])
-            .map_err(|e| Error::InvalidGrokExpression(rule[start..end].to_string(), e))?;
+        let pattern = parse_grok_pattern(&rule[start..end]).map_err(|e| {
+            Error::InvalidGrokExpression(rule[start..end].to_string(), e, Some(start))
+        })?;
+        let regex_start = context.regex.len();
         resolve_grok_pattern(&pattern, context)?;
+        // offsets within alias definitions don't point into the rule
+        if context.alias_stack.is_empty() {
+            context
+                .expressions
+                .push((start, regex_start..context.regex.len()));
+        }
     }
     context.append_regex(&rule[regex_i..]);
 
@@ -269,6 +416,7 @@ fn resolve_grok_pattern(
         _ => {}
     }
 
+    let field_name = grok_alias.clone();
     let match_name = &pattern.match_fn.name;
     match context.aliases.get(match_name).cloned() {
         Some(alias_def) => match &grok_alias {
@@ -280,7 +428,10 @@ fn resolve_grok_pattern(
                 context.append_regex(")");
             }
             None => {
+                // keeps the definition together, e.g. an alternation, without capturing it
+                context.append_regex("(?:");
                 parse_alias(match_name, &alias_def, context)?;
+                context.append_regex(")");
             }
         },
         None if match_name == "regex" || match_name == "date" || match_name == "boolean" => {
@@ -310,6 +461,11 @@ fn resolve_grok_pattern(
         }
     }
 
+    // the filters of the field are complete now: the one implied by the matcher and the explicit one
+    if let Some(field) = field_name.and_then(|name| context.fields.get(&name)) {
+        validate_filter_chain(&field.filters)?;
+    }
+
     Ok(())
 }
 
@@ -361,6 +517,20 @@ fn resolves_match_function(
             context.append_regex("numberExtStr");
             Ok(())
         }
+        "semver" => {
+            if let Some(grok_alias) = &grok_alias {
+                context.register_filter(grok_alias, GrokFilter::Semver);
+            }
+            context.append_regex("semverStr");
+            Ok(())
+        }
+        "escapedQuotedString" => {
+            if let Some(grok_alias) = &grok_alias {
+                context.register_filter(grok_alias, GrokFilter::Unquote);
+            }
+            context.append_regex("escapedQuotedStringStr");
+            Ok(())
+        }
         "date" => {
             return match match_fn.args.as_ref() {
                 Some(args) if !args.is_empty() && args.len() <= 2 => {
@@ -426,6 +596,245 @@ fn resolves_match_function(
 mod tests {
     use super::*;
 
+    #[test]
+    fn supports_case_insensitive_rules() {
+        let pattern = ["user=%{notSpace:user} level=%{regex(\"info|error\"):level}".to_string()];
+
+        let rules = parse_grok_rules(&pattern, BTreeMap::new()).expect("couldn't parse rules");
+        assert!(rules[0].captures("user=bob level=info").is_some());
+        assert!(rules[0].captures("USER=bob Level=INFO").is_none());
+
+        let rules = parse_grok_rules_with_options(
+            &pattern,
+            BTreeMap::new(),
+            GrokCompileOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+        )
+        .expect("couldn't parse rules");
+        assert_eq!(
+            rules[0].captures("USER=bob Level=INFO"),
+            Some(HashMap::from([("grok0", "bob"), ("grok1", "INFO")]))
+        );
+    }
+
+    #[test]
+    fn supports_unanchored_rules() {
+        let pattern = ["status=%{integer:status}".to_string()];
+        let input = "GET /index.html status=200 took 5ms";
+
+        let rules = parse_grok_rules(&pattern, BTreeMap::new()).expect("couldn't parse rules");
+        assert!(rules[0].captures(input).is_none());
+        assert!(rules[0].captures("status=200").is_some());
+
+        let rules = parse_grok_rules_with_options(
+            &pattern,
+            BTreeMap::new(),
+            GrokCompileOptions {
+                match_mode: MatchMode::Anywhere,
+                ..Default::default()
+            },
+        )
+        .expect("couldn't parse rules");
+        assert_eq!(
+            rules[0].captures(input),
+            Some(HashMap::from([("grok0", "200")]))
+        );
+    }
+
+    #[test]
+    fn validates_each_rule() {
+        let patterns = [
+            "%{notSpace:user} %{integer:status}".to_string(),
+            "%{unknownMatcher:field}".to_string(),
+            "".to_string(),
+            "%{data:field:unknownFilter}".to_string(),
+            "%{alias}".to_string(),
+        ];
+        let aliases = BTreeMap::from([("alias".to_string(), "%{word:name}".to_string())]);
+
+        let results = validate_grok_rules(&patterns, aliases);
+
+        assert_eq!(results.len(), 5);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert_eq!(
+            results[3].as_ref().unwrap_err().to_string(),
+            "unknown filter 'unknownFilter'"
+        );
+        assert!(results[4].is_ok());
+    }
+
+    #[test]
+    fn reports_invalid_expression_offset() {
+        let error = parse_grok_rules(
+            &["user=%{notSpace:user} %{data:field:integer(}".to_string()],
+            BTreeMap::new(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::InvalidGrokExpression(ref expression, _, Some(22)) if expression == "%{data:field:integer(}"
+        ));
+        assert!(error.to_string().starts_with(
+            "failed to parse grok expression '%{data:field:integer(}' at offset 22: "
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_regex_offset() {
+        let error = parse_grok_rules(
+            &[r#"user=%{notSpace:user} %{regex("[a-"):field}"#.to_string()],
+            BTreeMap::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::InvalidGrokExpression(_, _, Some(22))
+        ));
+
+        // a regular expression that is only invalid as a whole is reported at the start of the rule
+        let error =
+            parse_grok_rules(&["(%{notSpace:user}".to_string()], BTreeMap::new()).unwrap_err();
+        assert!(matches!(error, Error::InvalidGrokExpression(_, _, Some(0))));
+    }
+
+    #[test]
+    fn returns_raw_captures() {
+        let rules = parse_grok_rules(
+            &["%{notSpace:user} %{integer:status}".to_string()],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+        let rule = &rules[0];
+
+        assert_eq!(
+            rule.captures("bob 200"),
+            Some(HashMap::from([("grok0", "bob"), ("grok1", "200")]))
+        );
+        assert_eq!(rule.fields["grok0"].lookup, LookupBuf::from("user"));
+        assert_eq!(rule.fields["grok1"].lookup, LookupBuf::from("status"));
+        assert!(matches!(
+            rule.fields["grok1"].filters[..],
+            [GrokFilter::Integer]
+        ));
+
+        assert_eq!(rule.captures("bob"), None);
+    }
+
+    #[test]
+    fn returns_destinations_in_rule_order() {
+        let rules = parse_grok_rules(
+            &["%{notSpace:http.user} %{integer:status} %{word}".to_string()],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+
+        assert_eq!(
+            rules[0].destinations(),
+            vec![
+                &LookupBuf::from_segments(vec!["http".into(), "user".into()]),
+                &LookupBuf::from("status"),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_repeated_destinations_once() {
+        let rules = parse_grok_rules(
+            &["%{word:tag} %{integer:status} %{word:tag} %{word:tag}".to_string()],
+            BTreeMap::new(),
+        )
+        .expect("couldn't parse rules");
+
+        assert_eq!(
+            rules[0].destinations(),
+            vec![&LookupBuf::from("tag"), &LookupBuf::from("status")]
+        );
+    }
+
+    #[test]
+    fn captures_only_destinations() {
+        let aliases = BTreeMap::from([
+            (
+                "outer".to_string(),
+                r#"%{word:user} %{inner} %{regex("a+")}"#.to_string(),
+            ),
+            (
+                "inner".to_string(),
+                r#"%{ip} %{hostname} %{integer:status} %{date("HH:mm")}"#.to_string(),
+            ),
+        ]);
+        let rules = parse_grok_rules(&["%{outer} %{notSpace}".to_string()], aliases)
+            .expect("couldn't parse rules");
+
+        assert_eq!(rules[0].pattern.capture_count(), 2);
+        assert_eq!(
+            rules[0].captures("bob 10.0.0.1 example.com 200 10:15 aaa x"),
+            Some(HashMap::from([("grok0", "bob"), ("grok1", "200")]))
+        );
+    }
+
+    #[test]
+    fn groups_aliases_without_destination() {
+        let aliases = BTreeMap::from([("status".to_string(), "%{integer:code}|-".to_string())]);
+        let rules = parse_grok_rules(&["%{notSpace:user} %{status}".to_string()], aliases)
+            .expect("couldn't parse rules");
+
+        assert_eq!(rules[0].pattern.capture_count(), 2);
+        assert_eq!(
+            rules[0].captures("bob -"),
+            Some(HashMap::from([("grok0", "bob"), ("grok1", "")]))
+        );
+        assert_eq!(rules[0].captures("-"), None);
+    }
+
+    #[test]
+    fn supports_backreferences_without_destinations() {
+        let rules = parse_grok_rules(&[r#"%{regex("(a|b)\\1")}"#.to_string()], BTreeMap::new())
+            .expect("couldn't parse rules");
+
+        assert!(rules[0].pattern.is_match("aa"));
+        assert!(!rules[0].pattern.is_match("ab"));
+    }
+
+    #[test]
+    fn accepts_compatible_filter_chains() {
+        for rule in [
+            "%{integer:field:scale(10)}",
+            "%{number:field:defaultTo(0)}",
+            "%{data:field:keyvalue}",
+            r#"%{date("HH:mm"):field:emptyStringIfNull}"#,
+        ] {
+            assert!(
+                parse_grok_rules(&[rule.to_string()], BTreeMap::new()).is_ok(),
+                "{}",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_incompatible_filter_chains() {
+        let error = parse_grok_rules(&["%{integer:field:uppercase}".to_string()], BTreeMap::new())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "filter 'Uppercase' can't be applied to the output of the filter 'Integer'"
+        );
+
+        assert!(matches!(
+            parse_grok_rules(
+                &[r#"%{date("HH:mm"):field:trim}"#.to_string()],
+                BTreeMap::new()
+            ),
+            Err(Error::IncompatibleFilters(..))
+        ));
+    }
+
     #[test]
     fn supports_escaped_quotes() {
         let rules = parse_grok_rules(
@@ -1,16 +1,21 @@
 use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 
+use chrono::{DateTime, TimeZone, Utc};
 use lookup::LookupBuf;
 use serde::{Deserialize, Serialize};
+use value::{kind::Collection, Kind};
 use vector_buffers::EventCount;
 use vector_common::EventDataEq;
 
 use super::{
     BatchNotifier, EventFinalizer, EventFinalizers, EventMetadata, Finalizable, LogEvent, Value,
 };
-use crate::ByteSizeOf;
+use crate::{config::log_schema, schema, ByteSizeOf};
 use lookup::path;
 
+/// The well-known timestamp fields of a trace, normalized by `TraceEvent::normalize_timestamps`.
+const TIMESTAMP_FIELDS: [&str; 3] = ["start_time", "end_time", "timestamp"];
+
 /// Traces are a newtype of `LogEvent`
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, PartialOrd, Serialize)]
 pub struct TraceEvent(LogEvent);
@@ -30,6 +35,37 @@ impl TraceEvent {
         Self(LogEvent::from_map(fields, metadata))
     }
 
+    /// The schema of a single span of a trace, e.g. of the log events emitted by the
+    /// `trace_to_spans` transform: the well-known span fields, which may be missing, along with
+    /// any other field.
+    pub fn span_schema_definition() -> schema::Definition {
+        [
+            ("service", Kind::bytes()),
+            ("name", Kind::bytes()),
+            ("resource", Kind::bytes()),
+            ("type", Kind::bytes()),
+            ("trace_id", Kind::integer()),
+            ("span_id", Kind::integer()),
+            ("parent_id", Kind::integer()),
+            ("start", Kind::timestamp()),
+            ("duration", Kind::integer()),
+            ("error", Kind::integer()),
+            (
+                "meta",
+                Kind::object(Collection::from_unknown(Kind::bytes())),
+            ),
+            (
+                "metrics",
+                Kind::object(Collection::from_unknown(Kind::float().or_null())),
+            ),
+        ]
+        .into_iter()
+        .fold(schema::Definition::empty(), |definition, (field, kind)| {
+            definition.optional_field(field, kind, None)
+        })
+        .unknown_fields(Kind::any())
+    }
+
     pub fn value(&self) -> &Value {
         self.0.value()
     }
@@ -68,6 +104,15 @@ impl TraceEvent {
         self.0.as_map().expect("inner value must be a map")
     }
 
+    /// Iterates over all leaf fields of the `TraceEvent` with their full paths, e.g. `spans[0].name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fields of the `TraceEvent` are not a `Value::Map`.
+    pub fn all_fields(&self) -> impl Iterator<Item = (String, &Value)> + Serialize {
+        self.0.all_fields().expect("inner value must be a map")
+    }
+
     pub fn get(&self, key: impl AsRef<str>) -> Option<&Value> {
         self.0.get(key.as_ref())
     }
@@ -99,6 +144,63 @@ impl TraceEvent {
     ) -> Option<Value> {
         self.0.insert(key.as_ref(), value.into())
     }
+
+    /// Returns the timestamp of the trace, stored in the field configured by the log schema's
+    /// `timestamp_key`, if it is a timestamp.
+    pub fn get_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.get(log_schema().timestamp_key())
+            .and_then(Value::as_timestamp)
+            .copied()
+    }
+
+    /// Moves the value of `from` to `to`, overwriting any existing value of `to`.
+    /// Returns whether `from` existed.
+    pub fn rename_key(&mut self, from: impl AsRef<str>, to: impl AsRef<str>) -> bool {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        if from == to {
+            return self.contains(from);
+        }
+        match self.0.remove(from) {
+            Some(value) => {
+                self.0.insert(to, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Converts the `start_time`, `end_time` and `timestamp` fields, given either as epoch
+    /// nanoseconds or as RFC3339 strings, into timestamps. Missing fields, as well as values
+    /// that can't be interpreted as a timestamp, are left as is.
+    pub fn normalize_timestamps(&mut self) {
+        for field in TIMESTAMP_FIELDS {
+            let timestamp = match self.get_flat(field) {
+                Some(Value::Integer(nanos)) => Some(Utc.timestamp_nanos(*nanos)),
+                Some(Value::Bytes(bytes)) => std::str::from_utf8(bytes)
+                    .ok()
+                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                    .map(|dt| dt.with_timezone(&Utc)),
+                _ => None,
+            };
+            if let Some(timestamp) = timestamp {
+                self.0.insert(path!(field), timestamp);
+            }
+        }
+    }
+
+    /// Applies `f` to every scalar value of the `TraceEvent`, descending into nested objects and
+    /// arrays. The event metadata is left untouched.
+    pub fn transform_values<F: FnMut(&mut Value)>(&mut self, mut f: F) {
+        fn transform<F: FnMut(&mut Value)>(value: &mut Value, f: &mut F) {
+            match value {
+                Value::Object(map) => map.values_mut().for_each(|value| transform(value, f)),
+                Value::Array(array) => array.iter_mut().for_each(|value| transform(value, f)),
+                value => f(value),
+            }
+        }
+
+        transform(self.value_mut(), &mut f);
+    }
 }
 
 impl From<LogEvent> for TraceEvent {
@@ -148,3 +250,163 @@ impl AsMut<LogEvent> for TraceEvent {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vrl_lib::value;
+
+    #[test]
+    fn sorts_by_timestamp() {
+        let trace = |seconds| {
+            let mut trace = TraceEvent::default();
+            trace.insert(log_schema().timestamp_key(), Utc.timestamp(seconds, 0));
+            trace
+        };
+        let mut traces = vec![trace(30), TraceEvent::default(), trace(10), trace(20)];
+
+        traces.sort_by_key(TraceEvent::get_timestamp);
+
+        assert_eq!(
+            traces
+                .iter()
+                .map(|trace| trace.get_timestamp().map(|ts| ts.timestamp()))
+                .collect::<Vec<_>>(),
+            vec![None, Some(10), Some(20), Some(30)]
+        );
+    }
+
+    #[test]
+    fn renames_keys() {
+        let mut trace = TraceEvent::from(LogEvent::from_parts(
+            value!({
+                resource: { service: { name: "api" } },
+                service: "unknown",
+                name: "GET /"
+            }),
+            EventMetadata::default(),
+        ));
+
+        // overwrites the existing value
+        assert!(trace.rename_key("resource.service.name", "service"));
+        assert_eq!(trace.get("service"), Some(&Value::from("api")));
+        assert_eq!(trace.get("resource.service.name"), None);
+
+        assert!(!trace.rename_key("missing", "other"));
+        assert!(!trace.contains("other"));
+
+        assert!(trace.rename_key("name", "name"));
+        assert_eq!(trace.get("name"), Some(&Value::from("GET /")));
+    }
+
+    #[test]
+    fn transforms_values_preserving_metadata() {
+        let mut metadata = EventMetadata::default();
+        metadata.set_datadog_api_key(Some(Arc::from("key")));
+        let mut trace = TraceEvent::from(LogEvent::from_parts(
+            value!({
+                name: "get",
+                duration: 42,
+                meta: { service: "api", tags: ["a", 1] }
+            }),
+            metadata.clone(),
+        ));
+
+        trace.transform_values(|value| {
+            if let Value::Bytes(bytes) = value {
+                *bytes = bytes.to_ascii_uppercase().into();
+            }
+        });
+
+        assert_eq!(
+            trace.value(),
+            &value!({
+                name: "GET",
+                duration: 42,
+                meta: { service: "API", tags: ["A", 1] }
+            })
+        );
+        assert_eq!(trace.metadata(), &metadata);
+    }
+
+    #[test]
+    fn describes_span_fields() {
+        let definition = TraceEvent::span_schema_definition();
+        let fields = definition.collection().known();
+
+        assert_eq!(fields.get(&"span_id".into()), Some(&Kind::integer()));
+        assert_eq!(fields.get(&"start".into()), Some(&Kind::timestamp()));
+        assert!(definition.collection().unknown().is_some());
+
+        // each of the span fields is optional
+        let kind = Kind::from(definition);
+        let fields = kind.as_object().expect("span must be an object").known();
+        assert_eq!(
+            fields.get(&"trace_id".into()),
+            Some(&Kind::integer().or_null())
+        );
+    }
+
+    #[test]
+    fn normalizes_timestamps() {
+        let mut trace = TraceEvent::from(BTreeMap::from([
+            (
+                "start_time".to_owned(),
+                Value::Integer(1_600_000_000_123_456_789),
+            ),
+            (
+                "end_time".to_owned(),
+                Value::from("2020-09-13T12:26:41.5+02:00"),
+            ),
+            ("timestamp".to_owned(), Value::from("yesterday")),
+            ("name".to_owned(), Value::Integer(1)),
+        ]));
+
+        trace.normalize_timestamps();
+
+        assert_eq!(
+            trace.get("start_time"),
+            Some(&Value::Timestamp(Utc.timestamp(1_600_000_000, 123_456_789)))
+        );
+        assert_eq!(
+            trace.get("end_time"),
+            Some(&Value::Timestamp(Utc.timestamp(1_599_992_801, 500_000_000)))
+        );
+        assert_eq!(trace.get("timestamp"), Some(&Value::from("yesterday")));
+        assert_eq!(trace.get("name"), Some(&Value::Integer(1)));
+    }
+
+    #[test]
+    fn all_fields_flattens_nested_spans() {
+        let value = value!({
+            trace_id: 123,
+            spans: [
+                {
+                    name: "parent",
+                    meta: { service: "api" }
+                },
+                {
+                    name: "child",
+                    tags: ["a", "b"]
+                }
+            ]
+        });
+        let trace = TraceEvent::from(LogEvent::from_parts(value, EventMetadata::default()));
+
+        let fields: Vec<_> = trace
+            .all_fields()
+            .map(|(path, value)| (path, value.clone()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("spans[0].meta.service".to_owned(), Value::from("api")),
+                ("spans[0].name".to_owned(), Value::from("parent")),
+                ("spans[1].name".to_owned(), Value::from("child")),
+                ("spans[1].tags[0]".to_owned(), Value::from("a")),
+                ("spans[1].tags[1]".to_owned(), Value::from("b")),
+                ("trace_id".to_owned(), Value::Integer(123)),
+            ]
+        );
+    }
+}
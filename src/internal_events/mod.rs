@@ -137,6 +137,8 @@ mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
 mod throttle;
+#[cfg(feature = "transforms-trace_to_spans")]
+mod trace_to_spans;
 mod udp;
 mod unix;
 mod vector;
@@ -296,6 +298,8 @@ pub(crate) use self::syslog::*;
 pub(crate) use self::tag_cardinality_limit::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
+#[cfg(feature = "transforms-trace_to_spans")]
+pub(crate) use self::trace_to_spans::*;
 #[cfg(all(
     any(
         feature = "sinks-socket",
@@ -0,0 +1,33 @@
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use super::prelude::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct TraceToSpansMissingSpans<'a> {
+    pub spans_field: &'a str,
+}
+
+impl<'a> InternalEvent for TraceToSpansMissingSpans<'a> {
+    fn emit(self) {
+        error!(
+            message = "Trace has no spans; discarding event.",
+            spans_field = %self.spans_field,
+            error_code = "missing_spans",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "missing_spans",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
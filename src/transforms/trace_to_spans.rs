@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, Output, TransformConfig, TransformContext,
+        TransformDescription,
+    },
+    event::{Event, EventStatus, LogEvent, TraceEvent, Value},
+    internal_events::TraceToSpansMissingSpans,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TraceToSpansConfig {
+    /// The path of the spans array in the trace, e.g. `payload.spans`.
+    #[serde(default = "default_spans_field")]
+    pub spans_field: String,
+    /// The path of the trace id, in the trace as well as in the emitted spans.
+    #[serde(default = "default_trace_id_field")]
+    pub trace_id_field: String,
+}
+
+fn default_spans_field() -> String {
+    "spans".to_string()
+}
+
+fn default_trace_id_field() -> String {
+    "trace_id".to_string()
+}
+
+impl Default for TraceToSpansConfig {
+    fn default() -> Self {
+        Self {
+            spans_field: default_spans_field(),
+            trace_id_field: default_trace_id_field(),
+        }
+    }
+}
+
+inventory::submit! {
+    TransformDescription::new::<TraceToSpansConfig>("trace_to_spans")
+}
+
+impl GenerateConfig for TraceToSpansConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "trace_to_spans")]
+impl TransformConfig for TraceToSpansConfig {
+    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
+        Ok(Transform::function(TraceToSpans::new(self)))
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn outputs(&self, _: &schema::Definition) -> Vec<Output> {
+        vec![Output::default(DataType::Log)
+            .with_schema_definition(TraceEvent::span_schema_definition())]
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+
+    fn transform_type(&self) -> &'static str {
+        "trace_to_spans"
+    }
+}
+
+/// Emits one log event per span of a trace, carrying the trace id along with the span fields.
+/// Each span event shares the finalizers of the trace, so the trace is only acknowledged once
+/// all of its spans are. A trace without any object span is discarded and rejected.
+#[derive(Clone, Debug)]
+pub struct TraceToSpans {
+    spans_field: String,
+    trace_id_field: String,
+}
+
+impl TraceToSpans {
+    pub fn new(config: &TraceToSpansConfig) -> Self {
+        Self {
+            spans_field: config.spans_field.clone(),
+            trace_id_field: config.trace_id_field.clone(),
+        }
+    }
+}
+
+impl FunctionTransform for TraceToSpans {
+    fn transform(&mut self, output: &mut OutputBuffer, event: Event) {
+        let (fields, metadata) = event.into_trace().into_parts();
+        let mut trace = LogEvent::from_map(fields, metadata);
+        let trace_id = trace.get(self.trace_id_field.as_str()).cloned();
+
+        let spans = match trace.remove(self.spans_field.as_str()) {
+            Some(Value::Array(spans)) if spans.iter().any(Value::is_object) => spans,
+            _ => {
+                emit!(TraceToSpansMissingSpans {
+                    spans_field: &self.spans_field
+                });
+                trace.metadata().update_status(EventStatus::Rejected);
+                return;
+            }
+        };
+
+        let (_, metadata) = trace.into_parts();
+        // spans that aren't objects don't produce any event
+        for span in spans {
+            if let Value::Object(span) = span {
+                let mut span = LogEvent::from_map(span, metadata.clone());
+                if let Some(trace_id) = &trace_id {
+                    if !span.contains(self.trace_id_field.as_str()) {
+                        span.insert(self.trace_id_field.as_str(), trace_id.clone());
+                    }
+                }
+                output.push(span.into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use value::Kind;
+    use vector_common::btreemap;
+    use vector_core::event::{BatchNotifier, BatchStatus};
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TraceToSpansConfig>();
+    }
+
+    #[test]
+    fn outputs_span_schema() {
+        let outputs = TraceToSpansConfig::default().outputs(&schema::Definition::empty());
+        let definition = outputs[0]
+            .log_schema_definition
+            .clone()
+            .expect("span schema must be set");
+
+        assert_eq!(definition, TraceEvent::span_schema_definition());
+        assert_eq!(
+            definition.collection().known().get(&"span_id".into()),
+            Some(&Kind::integer())
+        );
+    }
+
+    #[test]
+    fn emits_one_event_per_span() {
+        fn span(id: i64, name: &str) -> BTreeMap<String, Value> {
+            btreemap! { "span_id" => id, "name" => name }
+        }
+        // a span having its own trace id keeps it
+        let mut grandchild = span(3, "grandchild");
+        grandchild.insert("trace_id".to_owned(), Value::from(456));
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let fields: BTreeMap<String, Value> = btreemap! {
+            "trace_id" => 123,
+            "service" => "api",
+            "spans" => vec![
+                Value::from(span(1, "parent")),
+                Value::from(span(2, "child")),
+                Value::from(grandchild),
+            ],
+        };
+        let trace = TraceEvent::from(fields).with_batch_notifier(&batch);
+        drop(batch);
+
+        let mut transform = TraceToSpans::new(&TraceToSpansConfig::default());
+        let mut output = OutputBuffer::default();
+        transform.transform(&mut output, trace.into());
+
+        let events = output.into_events().collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        for (event, (span_id, trace_id)) in events.iter().zip([(1, 123), (2, 123), (3, 456)]) {
+            let log = event.as_log();
+            assert_eq!(log.get("span_id"), Some(&Value::from(span_id)));
+            assert_eq!(log.get("trace_id"), Some(&Value::from(trace_id)));
+            assert_eq!(log.get("service"), None);
+            assert_eq!(log.metadata().finalizers().len(), 1);
+        }
+
+        assert!(receiver.try_recv().is_err());
+        drop(events);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[test]
+    fn reads_nested_fields() {
+        let fields: BTreeMap<String, Value> = btreemap! {
+            "meta" => btreemap! { "trace_id" => 123 },
+            "payload" => btreemap! {
+                "spans" => vec![Value::from(btreemap! { "span_id" => 1 })],
+            },
+        };
+
+        let mut transform = TraceToSpans::new(&TraceToSpansConfig {
+            spans_field: "payload.spans".to_owned(),
+            trace_id_field: "meta.trace_id".to_owned(),
+        });
+        let mut output = OutputBuffer::default();
+        transform.transform(&mut output, TraceEvent::from(fields).into());
+
+        let events = output.into_events().collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log.get("span_id"), Some(&Value::from(1)));
+        assert_eq!(log.get("meta.trace_id"), Some(&Value::from(123)));
+    }
+
+    #[test]
+    fn rejects_trace_without_spans() {
+        let mut transform = TraceToSpans::new(&TraceToSpansConfig::default());
+
+        for spans in [
+            None,
+            Some(Value::from("span")),
+            Some(Value::from(vec![1, 2])),
+        ] {
+            let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+            let mut fields: BTreeMap<String, Value> = btreemap! { "trace_id" => 123 };
+            if let Some(spans) = spans {
+                fields.insert("spans".to_owned(), spans);
+            }
+            let trace = TraceEvent::from(fields).with_batch_notifier(&batch);
+            drop(batch);
+
+            let mut output = OutputBuffer::default();
+            transform.transform(&mut output, trace.into());
+
+            assert_eq!(output.into_events().count(), 0);
+            assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+        }
+    }
+}
@@ -0,0 +1,58 @@
+package metadata
+
+components: transforms: trace_to_spans: {
+	title: "Trace to Spans"
+
+	description: """
+		Converts a trace event into one log event per span, carrying the trace ID along with
+		the span fields. This is useful for sending spans to downstream components that expect
+		one event per span.
+		"""
+
+	classes: {
+		commonly_used: false
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		convert: {}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		spans_field: {
+			common:      false
+			description: "The path of the array of spans in the trace, e.g. `payload.spans`. Traces without any object span are discarded and rejected."
+			required:    false
+			type: string: {
+				default: "spans"
+			}
+		}
+		trace_id_field: {
+			common:      false
+			description: "The path of the trace ID in the trace, copied to the same path of each span unless the span already has it."
+			required:    false
+			type: string: {
+				default: "trace_id"
+			}
+		}
+	}
+
+	input: {
+		logs:    false
+		metrics: null
+		traces:  true
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+	}
+}